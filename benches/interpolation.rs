@@ -0,0 +1,140 @@
+//! Benchmarks for the per-entity systems of the interpolation.
+//! Run with `cargo bench --features testing`.
+
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "2d")]
+use avian2d::{
+    math::{Scalar, Vector},
+    prelude::*,
+};
+#[cfg(feature = "3d")]
+use avian3d::{
+    math::{Scalar, Vector},
+    prelude::*,
+};
+#[cfg(feature = "2d")]
+use avian_interpolation2d::testing::{create_app, InterpolationTestAppExt as _};
+#[cfg(feature = "3d")]
+use avian_interpolation3d::testing::{create_app, InterpolationTestAppExt as _};
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const ENTITY_COUNTS: [usize; 3] = [1_000, 10_000, 100_000];
+
+fn setup_app(entity_count: usize) -> App {
+    let mut app = create_app();
+    app.world_mut().spawn_batch((0..entity_count).map(|i| {
+        (
+            RigidBody::Kinematic,
+            Position(Vector::X * i as Scalar),
+            Rotation::default(),
+            LinearVelocity(Vector::Y),
+            TransformBundle::default(),
+        )
+    }));
+    app.advance_fixed_steps(1);
+    app
+}
+
+/// Like [`setup_app`], but all bodies are children of a single rotated parent, e.g. a level root.
+fn setup_parented_app(entity_count: usize) -> App {
+    let mut app = create_app();
+    let parent = app
+        .world_mut()
+        .spawn(TransformBundle::from_transform(Transform::from_rotation(
+            Quat::from_rotation_z(0.1),
+        )))
+        .id();
+    let children: Vec<Entity> = app
+        .world_mut()
+        .spawn_batch((0..entity_count).map(|i| {
+            (
+                RigidBody::Kinematic,
+                Position(Vector::X * i as Scalar),
+                Rotation::default(),
+                LinearVelocity(Vector::Y),
+                TransformBundle::default(),
+            )
+        }))
+        .collect();
+    app.world_mut().entity_mut(parent).push_children(&children);
+    app.advance_fixed_steps(1);
+    app
+}
+
+fn cache_previous_transform(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cache_previous_transform");
+    for entity_count in ENTITY_COUNTS {
+        let mut app = setup_app(entity_count);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(entity_count),
+            &entity_count,
+            |b, _| {
+                b.iter_custom(|iterations| {
+                    let mut elapsed = Duration::ZERO;
+                    for _ in 0..iterations {
+                        // Only changed positions are cached, so we change all of them.
+                        let world = app.world_mut();
+                        for mut position in world.query::<&mut Position>().iter_mut(world) {
+                            position.set_changed();
+                        }
+                        let start = Instant::now();
+                        app.run_cache_previous_transform();
+                        elapsed += start.elapsed();
+                    }
+                    elapsed
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn interpolate_rigid_bodies(c: &mut Criterion) {
+    bench_interpolation(c, "interpolate_rigid_bodies", setup_app);
+}
+
+fn interpolate_parented_rigid_bodies(c: &mut Criterion) {
+    bench_interpolation(c, "interpolate_parented_rigid_bodies", setup_parented_app);
+}
+
+fn bench_interpolation(c: &mut Criterion, name: &str, setup: fn(usize) -> App) {
+    let mut group = c.benchmark_group(name);
+    for entity_count in ENTITY_COUNTS {
+        let mut app = setup(entity_count);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(entity_count),
+            &entity_count,
+            |b, _| {
+                b.iter_custom(|iterations| {
+                    let mut elapsed = Duration::ZERO;
+                    for _ in 0..iterations {
+                        // Transforms that are already at the interpolated value are not written,
+                        // so we move them away to measure the full cost.
+                        let world = app.world_mut();
+                        for mut transform in world
+                            .query_filtered::<&mut Transform, With<RigidBody>>()
+                            .iter_mut(world)
+                        {
+                            transform.translation = Vec3::splat(1.0e6);
+                        }
+                        let start = Instant::now();
+                        app.run_interpolate_rigid_bodies();
+                        elapsed += start.elapsed();
+                    }
+                    elapsed
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    cache_previous_transform,
+    interpolate_rigid_bodies,
+    interpolate_parented_rigid_bodies
+);
+criterion_main!(benches);
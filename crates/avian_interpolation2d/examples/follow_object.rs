@@ -24,7 +24,6 @@ fn main() {
             handle_input.before(run_fixed_main_schedule),
         )
         .add_systems(FixedUpdate, clear_accumulated_input)
-        .add_systems(Update, follow_target)
         .run();
 }
 
@@ -36,8 +35,6 @@ fn setup(
     let prop_material = materials.add(Color::from(tailwind::EMERALD_300));
     let pillar_material = materials.add(Color::from(tailwind::RED_300));
 
-    commands.spawn((Name::new("Player Camera"), Camera2dBundle::default()));
-
     let tile_mesh = Mesh2dHandle(meshes.add(Rectangle::from_size(Vec2::splat(200.0))));
 
     // Take a look at this reference background while running the example to see the effect of the interpolation.
@@ -61,17 +58,26 @@ fn setup(
     }
 
     let box_shape = Rectangle::from_size(Vec2::splat(50.));
+    let player = commands
+        .spawn((
+            Name::new("Box"),
+            MaterialMesh2dBundle {
+                mesh: meshes.add(box_shape).into(),
+                material: prop_material.clone(),
+                ..default()
+            },
+            RigidBody::Kinematic,
+            Collider::from(box_shape),
+            AccumulatedInput::default(),
+            Moving,
+        ))
+        .id();
+
     commands.spawn((
-        Name::new("Box"),
-        MaterialMesh2dBundle {
-            mesh: meshes.add(box_shape).into(),
-            material: prop_material.clone(),
-            ..default()
-        },
-        RigidBody::Kinematic,
-        Collider::from(box_shape),
-        AccumulatedInput::default(),
-        Moving,
+        Name::new("Player Camera"),
+        Camera2dBundle::default(),
+        // Follows the rendered position of the player, so the camera does not jitter.
+        InterpolatedFollow::new(player),
     ));
 }
 
@@ -88,19 +94,6 @@ fn clear_accumulated_input(mut inputs: Query<&mut AccumulatedInput, With<Moving>
     }
 }
 
-fn follow_target(
-    mut cameras: Query<&mut Transform, With<Camera>>,
-    target: Query<&Transform, (With<Moving>, Without<Camera>)>,
-) {
-    for mut transform in &mut cameras {
-        for target_transform in &target {
-            let target = target_transform.translation;
-            transform.translation.x = target.x;
-            transform.translation.y = target.y;
-        }
-    }
-}
-
 /// Handle keyboard input and accumulate it in the `AccumulatedInput` component.
 /// There are many strategies for how to handle all the input that happened since the last fixed timestep.
 /// This is a very simple one: we just accumulate the input and average it out by normalizing it.
@@ -21,7 +21,6 @@ fn main() {
             handle_input.before(run_fixed_main_schedule),
         )
         .add_systems(FixedUpdate, clear_accumulated_input)
-        .add_systems(Update, follow_target.chain())
         .run();
 }
 
@@ -39,15 +38,6 @@ fn setup(
     let prop_material = materials.add(Color::from(tailwind::EMERALD_300));
     let pillar_material = materials.add(Color::from(tailwind::RED_300));
 
-    commands.spawn((
-        Name::new("Player Camera"),
-        Camera3dBundle {
-            // top-down view
-            transform: Transform::from_xyz(0.0, 8.0, 0.0).looking_at(Vec3::ZERO, Vec3::Y),
-            ..default()
-        },
-    ));
-
     commands.spawn((
         Name::new("Light"),
         PointLightBundle {
@@ -84,18 +74,31 @@ fn setup(
     }
 
     let box_shape = Cuboid::from_size(Vec3::splat(0.5));
+    let player = commands
+        .spawn((
+            Name::new("Box"),
+            PbrBundle {
+                mesh: meshes.add(Mesh::from(box_shape)),
+                material: prop_material.clone(),
+                transform: Transform::from_xyz(0.0, 1.5, 0.0),
+                ..default()
+            },
+            RigidBody::Kinematic,
+            Collider::from(box_shape),
+            AccumulatedInput::default(),
+            Moving,
+        ))
+        .id();
+
     commands.spawn((
-        Name::new("Box"),
-        PbrBundle {
-            mesh: meshes.add(Mesh::from(box_shape)),
-            material: prop_material.clone(),
-            transform: Transform::from_xyz(0.0, 1.5, 0.0),
+        Name::new("Player Camera"),
+        Camera3dBundle {
+            // top-down view
+            transform: Transform::from_xyz(0.0, 8.0, 0.0).looking_at(Vec3::ZERO, Vec3::Y),
             ..default()
         },
-        RigidBody::Kinematic,
-        Collider::from(box_shape),
-        AccumulatedInput::default(),
-        Moving,
+        // Follows the rendered position of the player, so the camera does not jitter.
+        InterpolatedFollow::new(player).with_offset(Vec3::Y * 6.5),
     ));
 }
 
@@ -134,16 +137,3 @@ fn clear_accumulated_input(mut inputs: Query<&mut AccumulatedInput, With<Moving>
         input.0 = Vec2::ZERO;
     }
 }
-
-fn follow_target(
-    mut cameras: Query<&mut Transform, With<Camera>>,
-    target: Query<&Transform, (With<Moving>, Without<Camera>)>,
-) {
-    for mut transform in &mut cameras {
-        for target_transform in &target {
-            let target = target_transform.translation;
-            transform.translation.x = target.x;
-            transform.translation.z = target.z;
-        }
-    }
-}
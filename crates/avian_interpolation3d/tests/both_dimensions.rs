@@ -0,0 +1,89 @@
+//! Checks that `avian_interpolation2d` and `avian_interpolation3d` can interpolate side by side in the same [`App`].
+
+use std::time::Duration;
+
+use avian2d::prelude::{
+    LinearVelocity as LinearVelocity2d, PhysicsPlugins as PhysicsPlugins2d, Position as Position2d,
+    RigidBody as RigidBody2d, Rotation as Rotation2d,
+};
+use avian3d::prelude::{
+    LinearVelocity as LinearVelocity3d, PhysicsPlugins as PhysicsPlugins3d, Position as Position3d,
+    RigidBody as RigidBody3d, Rotation as Rotation3d,
+};
+use avian_interpolation2d::{AvianInterpolation2dPlugin, PreviousPosition as PreviousPosition2d};
+use avian_interpolation3d::{AvianInterpolation3dPlugin, PreviousPosition as PreviousPosition3d};
+use bevy::{prelude::*, render::mesh::Mesh, scene::ScenePlugin, time::TimeUpdateStrategy};
+
+#[test]
+fn bodies_of_both_dimensions_are_interpolated() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+        PhysicsPlugins2d::default(),
+        PhysicsPlugins3d::default(),
+        AvianInterpolation2dPlugin::default(),
+        AvianInterpolation3dPlugin::default(),
+    ))
+    .init_asset::<Mesh>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    app.finish();
+    app.cleanup();
+    // The first update only initializes the clocks and does not advance them.
+    app.update();
+
+    let body_2d = app
+        .world_mut()
+        .spawn((
+            RigidBody2d::Kinematic,
+            Position2d::default(),
+            Rotation2d::default(),
+            LinearVelocity2d(avian2d::math::Vector::X * 10.0),
+            TransformBundle::default(),
+        ))
+        .id();
+    let body_3d = app
+        .world_mut()
+        .spawn((
+            RigidBody3d::Kinematic,
+            Position3d::default(),
+            Rotation3d::default(),
+            LinearVelocity3d(avian3d::math::Vector::X * 10.0),
+            TransformBundle::default(),
+        ))
+        .id();
+
+    let timestep = app.world().resource::<Time<Fixed>>().timestep();
+    advance_time(&mut app, timestep * 2);
+    advance_time(&mut app, timestep / 2);
+
+    let position = app.world().get::<Position2d>(body_2d).unwrap().0;
+    let previous_position = app.world().get::<PreviousPosition2d>(body_2d).unwrap().0;
+    assert_ne!(position, previous_position);
+    let expected =
+        avian2d::math::AsF32::f32(previous_position + (position - previous_position) * 0.5);
+    let translation = app.world().get::<Transform>(body_2d).unwrap().translation;
+    assert!(
+        translation.truncate().distance(expected) < 1e-2,
+        "2D body is at {translation} instead of {expected}"
+    );
+
+    let position = app.world().get::<Position3d>(body_3d).unwrap().0;
+    let previous_position = app.world().get::<PreviousPosition3d>(body_3d).unwrap().0;
+    assert_ne!(position, previous_position);
+    let expected =
+        avian3d::math::AsF32::f32(previous_position + (position - previous_position) * 0.5);
+    let translation = app.world().get::<Transform>(body_3d).unwrap().translation;
+    assert!(
+        translation.distance(expected) < 1e-2,
+        "3D body is at {translation} instead of {expected}"
+    );
+}
+
+fn advance_time(app: &mut App, duration: Duration) {
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+    app.update();
+}
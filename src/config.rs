@@ -0,0 +1,118 @@
+use std::num::NonZeroU32;
+
+use avian::math::Scalar;
+use bevy::ecs::batching::BatchingStrategy;
+
+use crate::prelude::*;
+
+/// Global configuration of [`AvianInterpolationPlugin`].
+/// Set it through [`AvianInterpolationPlugin::with_config`] or change the resource at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Resource, Reflect)]
+#[reflect(Resource, Default, PartialEq)]
+pub struct InterpolationConfig {
+    /// How many entities each task processes when the per-entity systems iterate over the rigid bodies in parallel.
+    /// `None` lets Bevy pick a batch size based on the number of entities and available threads. This is the default.
+    ///
+    /// Lower values spread small workloads over more threads, higher values reduce the scheduling overhead for
+    /// large entity counts.
+    pub batch_size: Option<usize>,
+
+    /// The interpolated translation is only written to the [`Transform`] if it is further away than this
+    /// from the current translation. This avoids triggering change detection for movements too small to see.
+    ///
+    /// `None` derives the threshold from the [`PhysicsLengthUnit`] as a thousandth of a length unit. This is the default.
+    pub translation_threshold: Option<Scalar>,
+
+    /// The interpolated rotation is only written to the [`Transform`] if it differs by more than this angle
+    /// from the current rotation, in radians. The default is `2.0 * 0.9999_f32.acos()`, about 1.6°,
+    /// which corresponds to a dot product of `0.9999` between the rotations and stays well clear of `f32` precision.
+    pub rotation_threshold: f32,
+
+    /// Bodies that move further than this in a single physics step are snapped to their new position instead of
+    /// being interpolated, which sends an [`InterpolationSnapped`] event.
+    /// Use this to avoid bodies visibly flying across the level when you teleport them by setting their [`Position`].
+    ///
+    /// `None` disables the teleport detection. This is the default.
+    pub teleport_threshold: Option<Scalar>,
+
+    /// When the interpolation happens. The default is [`InterpolationTiming::FixedMainLoop`].
+    pub timing: InterpolationTiming,
+
+    /// The maximum number of fixed steps to run in a single frame.
+    /// When a frame takes so long that more steps are due, e.g. after a hitch or on a machine that cannot keep up
+    /// with the physics, the remaining steps are skipped and all bodies are snapped to their current physics pose,
+    /// which sends an [`InterpolationSnapped`] event for each of them. This prevents the "spiral of death",
+    /// where catching up on the physics makes every frame slower than the last.
+    /// See [`FixedStepsThisFrame`] for how many steps ran.
+    ///
+    /// `None` runs as many steps as are due. This is the default.
+    pub max_fixed_steps_per_frame: Option<NonZeroU32>,
+}
+
+/// When [`AvianInterpolationPlugin`] interpolates, see [`InterpolationConfig::timing`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum InterpolationTiming {
+    /// Interpolate right after the fixed main loop, in [`RunFixedMainLoop`](bevy::app::RunFixedMainLoop).
+    /// The interpolated [`Transform`]s are available to all systems in [`Update`], e.g. for making a camera follow a body.
+    #[default]
+    FixedMainLoop,
+    /// Interpolate as late as possible, right before the render world extracts the [`GlobalTransform`]s.
+    /// The time that passed since the start of the frame is taken into account, which reduces the visual error
+    /// when a lot happens between the fixed main loop and the end of the frame.
+    ///
+    /// The [`Transform`]s and [`GlobalTransform`]s in the main world stay at their physics values,
+    /// as if every body used [`InterpolationMode::Last`]. Only the [`GlobalTransform`]s seen by the render world
+    /// are interpolated: those of the rigid bodies, their [`VisualOf`] proxies, entities with [`InterpolatedFollow`]
+    /// or [`InterpolateLocalTransform`], and all of their descendants.
+    ///
+    /// To achieve this, the interpolated [`GlobalTransform`]s are written in a schedule that runs after [`Last`]
+    /// and restored in a schedule that runs before [`First`], both without triggering change detection.
+    /// Apart from the one updating [`PreviousRenderedTransform`], no system in the main world sees them.
+    Extraction,
+}
+
+impl Default for InterpolationConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: None,
+            translation_threshold: None,
+            rotation_threshold: 2.0 * 0.9999_f32.acos(),
+            teleport_threshold: None,
+            timing: InterpolationTiming::default(),
+            max_fixed_steps_per_frame: None,
+        }
+    }
+}
+
+impl InterpolationConfig {
+    /// How far to interpolate the [`Transform`]s in the main world between the previous and current physics transform.
+    pub(crate) fn main_world_alpha(&self, fixed_time: &Time<Fixed>) -> Scalar {
+        match self.timing {
+            // The overstep fraction is a value between 0 and 1 that tells us how far we are between two fixed timesteps.
+            InterpolationTiming::FixedMainLoop => fixed_time.overstep_fraction_f64() as Scalar,
+            InterpolationTiming::Extraction => 1.0,
+        }
+    }
+
+    pub(crate) fn batching_strategy(&self) -> BatchingStrategy {
+        match self.batch_size {
+            Some(batch_size) => BatchingStrategy::fixed(batch_size),
+            None => BatchingStrategy::new(),
+        }
+    }
+
+    pub(crate) fn translation_threshold(&self, length_unit: Option<&PhysicsLengthUnit>) -> Scalar {
+        self.translation_threshold.unwrap_or_else(|| {
+            let length_unit = length_unit.map_or(1.0, |length_unit| length_unit.0);
+            1e-3 * length_unit
+        })
+    }
+
+    /// The smallest absolute dot product between two rotations that are closer than [`Self::rotation_threshold`].
+    pub(crate) fn min_rotation_dot(&self) -> f32 {
+        // The dot product of two unit quaternions is the cosine of half the angle between them.
+        (self.rotation_threshold * 0.5).cos()
+    }
+}
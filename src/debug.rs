@@ -0,0 +1,131 @@
+use avian::math::AsF32 as _;
+use bevy::color::palettes::tailwind;
+
+use crate::prelude::*;
+
+/// A plugin that draws gizmos for every interpolated rigid body.
+/// Useful for tuning the fixed timestep or diagnosing stutter.
+///
+/// For each body, this draws
+/// - the previous physics position as a small, faded marker,
+/// - the current [`Position`] as a bigger marker,
+/// - a line between the two, which is the path the interpolation travels along,
+/// - the axes of the rendered [`GlobalTransform`], i.e. what actually ends up on screen.
+///
+/// Everything is color-coded by the [`InterpolationMode`] used for [`InterpolateTransformFields::translation`].
+/// The colors and sizes can be configured through [`InterpolationGizmos`]:
+///
+/// ```rust,no_run
+/// use bevy::prelude::*;
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+#[cfg_attr(feature = "2d", doc = "use avian_interpolation2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian_interpolation3d::prelude::*;")]
+/// App::new()
+///     .add_plugins((
+///         DefaultPlugins,
+///         PhysicsPlugins::default(),
+///         AvianInterpolationPlugin::default(),
+///         AvianInterpolationDebugPlugin::default(),
+///     ));
+/// ```
+#[derive(Default)]
+#[non_exhaustive]
+pub struct AvianInterpolationDebugPlugin;
+
+impl Plugin for AvianInterpolationDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<InterpolationGizmos>();
+        app.add_systems(
+            PostUpdate,
+            draw_interpolation_gizmos.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// The gizmo config group used by [`AvianInterpolationDebugPlugin`].
+#[derive(Debug, Clone, Reflect, GizmoConfigGroup)]
+pub struct InterpolationGizmos {
+    /// The color used for bodies using [`InterpolationMode::Linear`].
+    pub linear_color: Color,
+    /// The color used for bodies using [`InterpolationMode::Last`].
+    pub last_color: Color,
+    /// The color used for bodies using [`InterpolationMode::None`].
+    pub none_color: Color,
+    /// The radius of the marker drawn at the current [`Position`].
+    /// The marker for the previous physics position is drawn at half this size.
+    pub marker_radius: f32,
+    /// The length of the axes drawn at the rendered [`GlobalTransform`].
+    pub axes_length: f32,
+}
+
+impl Default for InterpolationGizmos {
+    fn default() -> Self {
+        Self {
+            linear_color: tailwind::EMERALD_400.into(),
+            last_color: tailwind::AMBER_400.into(),
+            none_color: tailwind::RED_400.into(),
+            marker_radius: 0.1,
+            axes_length: 0.5,
+        }
+    }
+}
+
+impl InterpolationGizmos {
+    fn color(&self, mode: InterpolationMode) -> Color {
+        match mode {
+            InterpolationMode::Linear => self.linear_color,
+            InterpolationMode::Last => self.last_color,
+            InterpolationMode::None => self.none_color,
+        }
+    }
+}
+
+fn draw_interpolation_gizmos(
+    mut gizmos: Gizmos<InterpolationGizmos>,
+    q_interpolant: Query<(
+        &Position,
+        &PreviousPosition,
+        &GlobalTransform,
+        Option<&InterpolateTransformFields>,
+    )>,
+    render_origin: Option<Res<RenderOrigin>>,
+) {
+    let config = gizmos.config_ext;
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    for (position, previous_position, global_transform, maybe_interpolate_transform_fields) in
+        &q_interpolant
+    {
+        let interpolate_transform_fields = maybe_interpolate_transform_fields
+            .copied()
+            .unwrap_or_default();
+        let color = config.color(interpolate_transform_fields.translation);
+        let previous_color = color.with_alpha(0.5);
+        let position = (position.0 - render_origin).f32();
+        let previous_position = (previous_position.0 - render_origin).f32();
+
+        #[cfg(feature = "2d")]
+        {
+            gizmos.circle_2d(
+                previous_position,
+                config.marker_radius * 0.5,
+                previous_color,
+            );
+            gizmos.circle_2d(position, config.marker_radius, color);
+            gizmos.line_2d(previous_position, position, previous_color);
+            gizmos.axes_2d(*global_transform, config.axes_length);
+        }
+        #[cfg(feature = "3d")]
+        {
+            gizmos.sphere(
+                previous_position,
+                Quat::IDENTITY,
+                config.marker_radius * 0.5,
+                previous_color,
+            );
+            gizmos.sphere(position, Quat::IDENTITY, config.marker_radius, color);
+            gizmos.line(previous_position, position, previous_color);
+            gizmos.axes(*global_transform, config.axes_length);
+        }
+    }
+}
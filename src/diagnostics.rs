@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    app::RunFixedMainLoop,
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    ecs::entity::EntityHashMap,
+    utils::{Duration, Instant},
+};
+
+use crate::{
+    extraction::{interpolate_global_transforms, InterpolateGlobalTransforms},
+    interpolate::Settled,
+    prelude::*,
+    visual_proxy::VisualProxies,
+};
+
+/// A plugin that registers [`Diagnostic`]s for the cost and the smoothness of the interpolation.
+/// Add it next to [`AvianInterpolationPlugin`] and read the values with e.g. Bevy's `LogDiagnosticsPlugin`.
+///
+/// The following diagnostics are registered:
+/// - [`Self::INTERPOLATED_ENTITIES`]: the number of interpolated rigid bodies that are not at rest.
+/// - [`Self::CACHE_PREVIOUS_TRANSFORM_TIME`]: the time spent caching the previous physics transforms this frame, in milliseconds.
+///   This accumulates over all fixed steps that ran this frame.
+/// - [`Self::INTERPOLATE_TIME`]: the time spent interpolating the transforms this frame, in milliseconds.
+/// - [`Self::FIXED_STEPS_PER_FRAME`]: the number of fixed steps that ran this frame, see [`FixedStepsThisFrame`].
+/// - [`Self::JITTER`]: the variance of the average per-frame displacement of the rendered transforms
+///   over the last [`Self::JITTER_WINDOW`] frames. Smooth motion at a steady frame rate keeps this close to zero.
+///   This uses the [`GlobalTransform`]s right before rendering, i.e. those of the [`VisualOf`] proxies instead of their
+///   rigid bodies, and takes [`InterpolationTiming::Extraction`] into account.
+///
+/// The timings are measured around [`AvianInterpolationFixedSystem::CachePreviousPhysicsTransform`]
+/// and [`AvianInterpolationVariableSystem::Interpolate`], so anything you add to these sets is measured as well.
+///
+/// All paths are prefixed with the name of this crate, so the diagnostics of `avian_interpolation2d`
+/// and `avian_interpolation3d` can be told apart when both are used in the same app.
+#[derive(Default)]
+#[non_exhaustive]
+pub struct AvianInterpolationDiagnosticsPlugin;
+
+macro_rules! diagnostic_path {
+    ($name:literal) => {
+        DiagnosticPath::const_new(concat!(env!("CARGO_PKG_NAME"), "/", $name))
+    };
+}
+
+impl AvianInterpolationDiagnosticsPlugin {
+    /// The number of interpolated rigid bodies that are not at rest.
+    pub const INTERPOLATED_ENTITIES: DiagnosticPath = diagnostic_path!("interpolated_entities");
+    /// The time spent caching the previous physics transforms this frame, in milliseconds.
+    pub const CACHE_PREVIOUS_TRANSFORM_TIME: DiagnosticPath =
+        diagnostic_path!("cache_previous_transform_time");
+    /// The time spent interpolating the transforms this frame, in milliseconds.
+    pub const INTERPOLATE_TIME: DiagnosticPath = diagnostic_path!("interpolate_time");
+    /// The number of fixed steps that ran this frame.
+    pub const FIXED_STEPS_PER_FRAME: DiagnosticPath = diagnostic_path!("fixed_steps_per_frame");
+    /// The variance of the average per-frame displacement of the rendered transforms.
+    pub const JITTER: DiagnosticPath = diagnostic_path!("jitter");
+
+    /// The number of frames over which [`Self::JITTER`] is computed.
+    pub const JITTER_WINDOW: usize = 60;
+}
+
+impl Plugin for AvianInterpolationDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InterpolationTimings>();
+        app.register_diagnostic(Diagnostic::new(Self::INTERPOLATED_ENTITIES))
+            .register_diagnostic(
+                Diagnostic::new(Self::CACHE_PREVIOUS_TRANSFORM_TIME).with_suffix("ms"),
+            )
+            .register_diagnostic(Diagnostic::new(Self::INTERPOLATE_TIME).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::FIXED_STEPS_PER_FRAME))
+            .register_diagnostic(Diagnostic::new(Self::JITTER));
+
+        app.add_systems(
+            FixedPreUpdate,
+            (
+                start_cache_timer
+                    .before(AvianInterpolationFixedSystem::CachePreviousPhysicsTransform),
+                stop_cache_timer
+                    .after(AvianInterpolationFixedSystem::CachePreviousPhysicsTransform),
+            )
+                // The set does not run while interpolation is disabled.
+                .run_if(resource_equals(InterpolationEnabled(true))),
+        );
+        app.add_systems(
+            RunFixedMainLoop,
+            (
+                start_interpolate_timer
+                    .after(AvianInterpolationVariableSystem::First)
+                    .before(AvianInterpolationVariableSystem::Interpolate),
+                stop_interpolate_timer
+                    .after(AvianInterpolationVariableSystem::Interpolate)
+                    .before(AvianInterpolationVariableSystem::Last),
+            ),
+        );
+        app.add_systems(
+            InterpolateGlobalTransforms,
+            measure_jitter.after(interpolate_global_transforms),
+        );
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+struct InterpolationTimings {
+    cache_start: Option<Instant>,
+    cache_time: Duration,
+    interpolate_start: Option<Instant>,
+}
+
+fn start_cache_timer(mut timings: ResMut<InterpolationTimings>) {
+    timings.cache_start = Some(Instant::now());
+}
+
+fn stop_cache_timer(mut timings: ResMut<InterpolationTimings>) {
+    if let Some(start) = timings.cache_start.take() {
+        timings.cache_time += start.elapsed();
+    }
+}
+
+fn start_interpolate_timer(mut timings: ResMut<InterpolationTimings>) {
+    timings.interpolate_start = Some(Instant::now());
+}
+
+fn stop_interpolate_timer(
+    mut diagnostics: Diagnostics,
+    mut timings: ResMut<InterpolationTimings>,
+    fixed_steps: Res<FixedStepsThisFrame>,
+    q_interpolant: Query<(), (With<PreviousPosition>, Without<Settled>)>,
+) {
+    if let Some(start) = timings.interpolate_start.take() {
+        let interpolate_time = start.elapsed();
+        diagnostics.add_measurement(
+            &AvianInterpolationDiagnosticsPlugin::INTERPOLATE_TIME,
+            || interpolate_time.as_secs_f64() * 1000.0,
+        );
+    }
+    let cache_time = std::mem::take(&mut timings.cache_time);
+    diagnostics.add_measurement(
+        &AvianInterpolationDiagnosticsPlugin::CACHE_PREVIOUS_TRANSFORM_TIME,
+        || cache_time.as_secs_f64() * 1000.0,
+    );
+    diagnostics.add_measurement(
+        &AvianInterpolationDiagnosticsPlugin::FIXED_STEPS_PER_FRAME,
+        || fixed_steps.steps as f64,
+    );
+    diagnostics.add_measurement(
+        &AvianInterpolationDiagnosticsPlugin::INTERPOLATED_ENTITIES,
+        || q_interpolant.iter().count() as f64,
+    );
+}
+
+#[derive(Debug, Default)]
+struct JitterState {
+    last_translations: EntityHashMap<Vec3>,
+    displacements: VecDeque<f64>,
+}
+
+/// Measures the rendered entities, which are the visual proxies for rigid bodies that have them.
+fn measure_jitter(
+    mut diagnostics: Diagnostics,
+    mut state: Local<JitterState>,
+    q_rendered: Query<
+        (Entity, &GlobalTransform),
+        Or<(
+            (With<PreviousPosition>, Without<VisualProxies>),
+            With<VisualOf>,
+        )>,
+    >,
+) {
+    let mut total_displacement = 0.0;
+    let mut count = 0;
+    let mut last_translations = EntityHashMap::default();
+    for (entity, global_transform) in &q_rendered {
+        let translation = global_transform.translation();
+        if let Some(last_translation) = state.last_translations.get(&entity) {
+            total_displacement += translation.distance(*last_translation) as f64;
+            count += 1;
+        }
+        last_translations.insert(entity, translation);
+    }
+    state.last_translations = last_translations;
+    if count == 0 {
+        return;
+    }
+
+    state
+        .displacements
+        .push_back(total_displacement / count as f64);
+    if state.displacements.len() > AvianInterpolationDiagnosticsPlugin::JITTER_WINDOW {
+        state.displacements.pop_front();
+    }
+    let len = state.displacements.len() as f64;
+    let mean = state.displacements.iter().sum::<f64>() / len;
+    let variance = state
+        .displacements
+        .iter()
+        .map(|displacement| (displacement - mean).powi(2))
+        .sum::<f64>()
+        / len;
+    diagnostics.add_measurement(&AvianInterpolationDiagnosticsPlugin::JITTER, || variance);
+}
@@ -0,0 +1,153 @@
+use crate::{interpolate::decompose, prelude::*};
+#[cfg(feature = "2d")]
+use avian::math::Quaternion;
+use avian::math::{AdjustPrecision as _, AsF32 as _};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DriveFromTransform>();
+    app.observe(insert_restored_transform)
+        .observe(remove_restored_transform);
+    app.add_systems(
+        FixedPreUpdate,
+        restore_physics_transform
+            .in_set(AvianInterpolationFixedSystem::CachePreviousPhysicsTransform),
+    );
+    app.add_systems(
+        FixedPostUpdate,
+        copy_transform_to_physics
+            .run_if(resource_equals(InterpolationEnabled(true)))
+            .before(PhysicsSet::Prepare),
+    );
+}
+
+/// Opt-in for rigid bodies that are driven by writing to their [`Transform`] in [`FixedUpdate`],
+/// e.g. kinematic platforms animated by curves.
+///
+/// Since [`AvianInterpolationPlugin`] disables Avian's transform syncing, writes to the [`Transform`]
+/// of a rigid body are usually overwritten by the interpolation. For entities with this component,
+/// the [`Transform`] is instead reset to the last physics transform before [`FixedUpdate`] runs,
+/// and any changes made to it in [`FixedUpdate`] are copied into [`Position`] and [`Rotation`] before the physics step.
+/// Afterwards, the [`Transform`] is interpolated as usual.
+///
+/// Only write to the [`Transform`] in [`FixedUpdate`], as changes made in other schedules will be overwritten.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq, Component, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct DriveFromTransform;
+
+/// The [`Transform`] as it was restored from the physics transform before [`FixedUpdate`].
+/// Only a [`Transform`] that differs from this was actually driven, so only then is it copied into the physics,
+/// which would otherwise lose precision to the `f32` [`Transform`] on every step.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+struct RestoredTransform(Transform);
+
+fn insert_restored_transform(
+    trigger: Trigger<OnAdd, DriveFromTransform>,
+    mut commands: Commands,
+    q_transform: Query<&Transform>,
+) {
+    let entity = trigger.entity();
+    let transform = q_transform.get(entity).copied().unwrap_or_default();
+    commands.entity(entity).insert(RestoredTransform(transform));
+}
+
+fn remove_restored_transform(
+    trigger: Trigger<OnRemove, DriveFromTransform>,
+    mut commands: Commands,
+) {
+    let entity = trigger.entity();
+    commands.entity(entity).remove::<RestoredTransform>();
+}
+
+fn restore_physics_transform(
+    mut q_driven: Query<(
+        &mut Transform,
+        &mut RestoredTransform,
+        Option<&Parent>,
+        &Position,
+        &Rotation,
+    )>,
+    q_global_transform: Query<&GlobalTransform>,
+    render_origin: Option<Res<RenderOrigin>>,
+) {
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    for (mut transform, mut restored_transform, maybe_parent, position, rotation) in &mut q_driven {
+        let position = (position.0 - render_origin).f32();
+        #[cfg(feature = "2d")]
+        let (translation, rotation) = (position.extend(0.), Quaternion::from(*rotation).f32());
+        #[cfg(feature = "3d")]
+        let (translation, rotation) = (position, rotation.0.f32());
+
+        // The physics works in render space, so apply the inverse of the parent's transform.
+        let Transform {
+            translation,
+            rotation,
+            ..
+        } = match maybe_parent.and_then(|parent| q_global_transform.get(parent.get()).ok()) {
+            Some(parent_global_transform) => GlobalTransform::from(
+                Transform::from_translation(translation).with_rotation(rotation),
+            )
+            .reparented_to(parent_global_transform),
+            None => Transform::from_translation(translation).with_rotation(rotation),
+        };
+        // In 2D, keep the z of the entity, as the physics has no notion of it.
+        #[cfg(feature = "2d")]
+        let translation = translation.truncate().extend(transform.translation.z);
+
+        let restored = Transform {
+            translation,
+            rotation,
+            ..*transform
+        };
+        transform.set_if_neq(restored);
+        restored_transform.0 = restored;
+    }
+}
+
+fn copy_transform_to_physics(
+    mut q_driven: Query<
+        (
+            &Transform,
+            &RestoredTransform,
+            Option<&Parent>,
+            &mut Position,
+            &mut Rotation,
+        ),
+        Changed<Transform>,
+    >,
+    q_global_transform: Query<&GlobalTransform>,
+    render_origin: Option<Res<RenderOrigin>>,
+) {
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    for (transform, restored_transform, maybe_parent, mut position, mut rotation) in &mut q_driven {
+        if *transform == restored_transform.0 {
+            continue;
+        }
+        // The physics works in render space, so apply the parent's transform.
+        let Transform {
+            translation,
+            rotation: new_rotation,
+            ..
+        } = match maybe_parent.and_then(|parent| q_global_transform.get(parent.get()).ok()) {
+            Some(parent_global_transform) => {
+                decompose(&parent_global_transform.mul_transform(*transform))
+            }
+            None => *transform,
+        };
+
+        #[cfg(feature = "2d")]
+        {
+            position.0 = translation.truncate().adjust_precision() + render_origin;
+            *rotation = Rotation::from(new_rotation.adjust_precision());
+        }
+        #[cfg(feature = "3d")]
+        {
+            position.0 = translation.adjust_precision() + render_origin;
+            rotation.0 = new_rotation.adjust_precision();
+        }
+    }
+}
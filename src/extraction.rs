@@ -0,0 +1,250 @@
+use avian::math::Scalar;
+use bevy::{
+    app::MainScheduleOrder,
+    ecs::{entity::EntityHashSet, schedule::ScheduleLabel},
+    utils::Duration,
+};
+
+use crate::{
+    config::InterpolationTiming,
+    interpolate::{interpolated_pose, Settled},
+    local_transform::LocalTransformSnapshot,
+    prelude::*,
+    visual_proxy::VisualProxies,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<StashedGlobalTransforms>();
+    app.init_schedule(InterpolateGlobalTransforms)
+        .init_schedule(RestoreGlobalTransforms);
+    let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+    order.insert_after(Last, InterpolateGlobalTransforms);
+    order.insert_before(First, RestoreGlobalTransforms);
+    app.add_systems(RestoreGlobalTransforms, restore_global_transforms);
+    app.add_systems(
+        InterpolateGlobalTransforms,
+        interpolate_global_transforms
+            .run_if(resource_equals(InterpolationEnabled(true)).and_then(extraction_timing)),
+    );
+}
+
+/// Runs after [`Last`], so the render world is the only one to see the [`GlobalTransform`]s written here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ScheduleLabel)]
+pub(crate) struct InterpolateGlobalTransforms;
+
+/// Runs before [`First`] and undoes [`InterpolateGlobalTransforms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ScheduleLabel)]
+pub(crate) struct RestoreGlobalTransforms;
+
+/// The [`GlobalTransform`]s as they were before [`interpolate_global_transforms`] overwrote them,
+/// so that the main world never sees the interpolated values.
+#[derive(Debug, Default, Resource)]
+struct StashedGlobalTransforms(Vec<(Entity, GlobalTransform)>);
+
+type HierarchyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static mut GlobalTransform,
+        Option<&'static Children>,
+        Option<&'static LocalTransformSnapshot>,
+    ),
+>;
+
+fn extraction_timing(config: Res<InterpolationConfig>) -> bool {
+    config.timing == InterpolationTiming::Extraction
+}
+
+/// Runs in [`InterpolateGlobalTransforms`], the latest point before the render world extracts the [`GlobalTransform`]s.
+/// The render world extracts all [`GlobalTransform`]s every frame, so writing them here is enough,
+/// and patching the extracted data of every kind of renderable instead would be far more brittle.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn interpolate_global_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    virtual_time: Res<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+    render_origin: Option<Res<RenderOrigin>>,
+    q_body: Query<
+        (
+            Entity,
+            &Position,
+            &Rotation,
+            &PreviousPosition,
+            &PreviousRotation,
+            Option<&InterpolateTransformFields>,
+            Has<VisualProxies>,
+        ),
+        Without<Settled>,
+    >,
+    q_proxy: Query<(Entity, &VisualOf)>,
+    q_follower: Query<(Entity, &InterpolatedFollow)>,
+    q_local: Query<(Entity, Option<&Parent>), With<LocalTransformSnapshot>>,
+    mut q_hierarchy: HierarchyQuery,
+    mut stash: ResMut<StashedGlobalTransforms>,
+    mut visited: Local<EntityHashSet>,
+) {
+    let alpha = late_overstep_fraction(&fixed_time, &virtual_time, &real_time);
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    let pose = |body: Entity, alpha: Scalar| {
+        let (
+            _,
+            position,
+            rotation,
+            previous_position,
+            previous_rotation,
+            maybe_interpolate_transform_fields,
+            _,
+        ) = q_body.get(body).ok()?;
+        Some(interpolated_pose(
+            alpha,
+            render_origin,
+            maybe_interpolate_transform_fields
+                .copied()
+                .unwrap_or_default(),
+            (position, rotation),
+            (previous_position, previous_rotation),
+        ))
+    };
+    let interpolated_global_transform = |body: Entity, global_transform: &GlobalTransform| {
+        let (translation, rotation) = pose(body, alpha)?;
+        let mut transform = global_transform.compute_transform();
+        if let Some(translation) = translation {
+            // In 2D, keep the z of the entity, as the physics has no notion of it.
+            #[cfg(feature = "2d")]
+            let translation = translation.truncate().extend(transform.translation.z);
+            transform.translation = translation;
+        }
+        if let Some(rotation) = rotation {
+            transform.rotation = rotation;
+        }
+        Some(GlobalTransform::from(transform))
+    };
+
+    // Bodies with visual proxies keep their physics transform, just like in the main world.
+    let bodies = q_body
+        .iter()
+        .filter(|(.., has_visual_proxies)| !has_visual_proxies)
+        .map(|(entity, ..)| (entity, entity));
+    let proxies = q_proxy
+        .iter()
+        .map(|(proxy, visual_of)| (proxy, visual_of.body()));
+    let mut roots = Vec::new();
+    for (entity, body) in bodies.chain(proxies) {
+        let Ok((_, global_transform, ..)) = q_hierarchy.get(entity) else {
+            continue;
+        };
+        if let Some(global_transform) = interpolated_global_transform(body, global_transform) {
+            roots.push((entity, global_transform));
+        }
+    }
+
+    // Followers follow the physics pose in the main world, so they are moved by as much as the interpolation
+    // moves their target. This also keeps their smoothing intact.
+    for (follower, follow) in &q_follower {
+        let (Some((Some(interpolated), _)), Some((Some(physics), _))) =
+            (pose(follow.target, alpha), pose(follow.target, 1.0))
+        else {
+            continue;
+        };
+        let Ok((_, global_transform, ..)) = q_hierarchy.get(follower) else {
+            continue;
+        };
+        let mut transform = global_transform.compute_transform();
+        transform.translation += interpolated - physics;
+        roots.push((follower, GlobalTransform::from(transform)));
+    }
+
+    visited.clear();
+    let local_alpha = alpha as f32;
+    propagate(
+        roots,
+        local_alpha,
+        &mut q_hierarchy,
+        &mut stash,
+        &mut visited,
+    );
+
+    // Entities with an interpolated local transform that are not below any of the above,
+    // e.g. a bobbing mesh below a static level root.
+    for (entity, maybe_parent) in &q_local {
+        if visited.contains(&entity) {
+            continue;
+        }
+        let parent_global_transform = maybe_parent
+            .and_then(|parent| q_hierarchy.get(parent.get()).ok())
+            .map_or(GlobalTransform::IDENTITY, |(_, global_transform, ..)| {
+                *global_transform
+            });
+        let Ok((.., Some(snapshot))) = q_hierarchy.get(entity) else {
+            continue;
+        };
+        let global_transform =
+            parent_global_transform.mul_transform(snapshot.interpolate(local_alpha));
+        propagate(
+            vec![(entity, global_transform)],
+            local_alpha,
+            &mut q_hierarchy,
+            &mut stash,
+            &mut visited,
+        );
+    }
+}
+
+/// Writes the interpolated [`GlobalTransform`]s of `roots` and moves their descendants along with them.
+fn propagate(
+    roots: Vec<(Entity, GlobalTransform)>,
+    local_alpha: f32,
+    q_hierarchy: &mut HierarchyQuery,
+    stash: &mut StashedGlobalTransforms,
+    visited: &mut EntityHashSet,
+) {
+    let mut stack = roots;
+    while let Some((entity, global_transform)) = stack.pop() {
+        let Ok((_, mut current_global_transform, maybe_children, _)) = q_hierarchy.get_mut(entity)
+        else {
+            continue;
+        };
+        visited.insert(entity);
+        stash.0.push((entity, *current_global_transform));
+        // Nothing changed as far as the main world is concerned, as the original is restored before `First`.
+        *current_global_transform.bypass_change_detection() = global_transform;
+        let children: Vec<Entity> = maybe_children
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+        for child in children {
+            if let Ok((transform, _, _, maybe_snapshot)) = q_hierarchy.get(child) {
+                let transform =
+                    maybe_snapshot.map_or(*transform, |snapshot| snapshot.interpolate(local_alpha));
+                stack.push((child, global_transform.mul_transform(transform)));
+            }
+        }
+    }
+}
+
+/// Like [`Time::overstep_fraction`], but also includes the time that passed since the start of the frame.
+fn late_overstep_fraction(
+    fixed_time: &Time<Fixed>,
+    virtual_time: &Time<Virtual>,
+    real_time: &Time<Real>,
+) -> Scalar {
+    let since_frame_start = real_time
+        .last_update()
+        .map_or(Duration::ZERO, |last_update| last_update.elapsed());
+    let overstep = fixed_time.overstep().as_secs_f64()
+        + since_frame_start.as_secs_f64() * virtual_time.effective_speed_f64();
+    // The physics has not simulated any further than its current transform, so we cannot go past it.
+    (overstep / fixed_time.timestep().as_secs_f64()).min(1.0) as Scalar
+}
+
+fn restore_global_transforms(
+    mut stash: ResMut<StashedGlobalTransforms>,
+    mut q_global_transform: Query<&mut GlobalTransform>,
+) {
+    // Restore in reverse, so that entities that were overwritten twice end up with their original value.
+    for (entity, global_transform) in stash.0.drain(..).rev() {
+        if let Ok(mut current_global_transform) = q_global_transform.get_mut(entity) {
+            *current_global_transform.bypass_change_detection() = global_transform;
+        }
+    }
+}
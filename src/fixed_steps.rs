@@ -0,0 +1,93 @@
+use bevy::{app::RunFixedMainLoop, time::run_fixed_main_schedule};
+
+use crate::{interpolate::Settled, prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FixedStepsThisFrame>();
+    app.init_resource::<FixedStepsThisFrame>();
+    app.add_systems(
+        RunFixedMainLoop,
+        (
+            reset_fixed_steps.before(run_fixed_main_schedule),
+            snap_after_capped_fixed_steps
+                .run_if(resource_equals(InterpolationEnabled(true)))
+                .after(run_fixed_main_schedule)
+                .before(AvianInterpolationVariableSystem::First),
+        ),
+    );
+    app.add_systems(FixedFirst, count_fixed_step);
+}
+
+/// How many fixed steps ran this frame. Zero means that the interpolation only advanced along the last physics step.
+///
+/// This is updated while the fixed main loop runs, so it is complete from [`AvianInterpolationVariableSystem::First`]
+/// onwards. Changing it has no effect.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Resource, Reflect)]
+#[reflect(Resource, Default, PartialEq)]
+pub struct FixedStepsThisFrame {
+    /// The number of fixed steps that ran this frame.
+    pub steps: u32,
+    /// Whether more fixed steps were due this frame than [`InterpolationConfig::max_fixed_steps_per_frame`] allows,
+    /// in which case the remaining ones were skipped.
+    pub capped: bool,
+}
+
+fn reset_fixed_steps(mut fixed_steps: ResMut<FixedStepsThisFrame>) {
+    *fixed_steps = default();
+}
+
+fn count_fixed_step(
+    config: Res<InterpolationConfig>,
+    mut fixed_steps: ResMut<FixedStepsThisFrame>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    fixed_steps.steps += 1;
+    let Some(max_fixed_steps) = config.max_fixed_steps_per_frame else {
+        return;
+    };
+    let timestep = fixed_time.timestep();
+    let overstep = fixed_time.overstep();
+    if fixed_steps.steps < max_fixed_steps.get() || overstep < timestep {
+        return;
+    }
+    // Skip all remaining whole steps, but keep the fraction of a step so the interpolation does not jump back.
+    let skipped_steps = (overstep.as_nanos() / timestep.as_nanos()) as u32;
+    fixed_time.discard_overstep(timestep * skipped_steps);
+    fixed_steps.capped = true;
+}
+
+/// After skipping fixed steps, the physics lag behind the real time, so interpolating between the last two
+/// physics steps would show an outdated pose. Instead, all bodies are snapped to their current physics pose.
+fn snap_after_capped_fixed_steps(
+    fixed_steps: Res<FixedStepsThisFrame>,
+    mut q_body: Query<
+        (
+            Entity,
+            &Position,
+            &Rotation,
+            &mut PreviousPosition,
+            &mut PreviousRotation,
+        ),
+        Without<Settled>,
+    >,
+    mut snapped: EventWriter<InterpolationSnapped>,
+) {
+    if !fixed_steps.capped {
+        return;
+    }
+    for (entity, position, rotation, mut previous_position, mut previous_rotation) in &mut q_body {
+        if previous_position.0 == position.0
+            && *previous_rotation == PreviousRotation::from(*rotation)
+        {
+            continue;
+        }
+        snapped.send(InterpolationSnapped {
+            entity,
+            from: previous_position.0,
+            to: position.0,
+            reason: SnapReason::FixedStepsCapped,
+        });
+        *previous_position = (*position).into();
+        *previous_rotation = (*rotation).into();
+    }
+}
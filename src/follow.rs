@@ -0,0 +1,166 @@
+use bevy::{
+    app::RunFixedMainLoop,
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+};
+
+use crate::{
+    interpolate::{interpolated_pose, relative_to_parent_global_transform, rendered_alpha},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<InterpolatedFollow>();
+    app.add_systems(
+        RunFixedMainLoop,
+        follow_interpolated_targets.in_set(AvianInterpolationVariableSystem::Follow),
+    );
+}
+
+/// Makes this entity, e.g. a camera, follow the rendered translation of the rigid body in [`Self::target`].
+///
+/// Following an interpolated body in [`Update`] is easy to get wrong, as a system running before the interpolation
+/// reads the pose of the last frame, which shows up as jitter. This component is evaluated in
+/// [`AvianInterpolationVariableSystem::Follow`], right after the interpolation, and always uses the pose
+/// the target is rendered at this frame. Only the translation of the follower is changed.
+///
+/// With [`InterpolationTiming::Extraction`], the follower uses the current physics pose of the target in the main world,
+/// and its rendered [`GlobalTransform`] is moved along with the rendered pose of the target.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct InterpolatedFollow {
+    /// The rigid body to follow.
+    pub target: Entity,
+    /// The offset from the rendered translation of the target, in world space.
+    #[cfg(feature = "3d")]
+    pub offset: Vec3,
+    /// The offset from the rendered translation of the target, in world space.
+    /// The follower keeps its own z so that e.g. a camera stays in front of the scene.
+    #[cfg(feature = "2d")]
+    pub offset: Vec2,
+    /// The time in seconds after which the follower has covered half of the distance to its destination.
+    /// Use `0.0` to follow the target rigidly, which is the default.
+    pub smoothing: f32,
+}
+
+impl MapEntities for InterpolatedFollow {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.target = entity_mapper.map_entity(self.target);
+    }
+}
+
+impl InterpolatedFollow {
+    /// Follows `target` rigidly and without an offset.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            offset: default(),
+            smoothing: 0.0,
+        }
+    }
+
+    /// Sets [`Self::offset`].
+    #[cfg(feature = "3d")]
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets [`Self::offset`].
+    #[cfg(feature = "2d")]
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets [`Self::smoothing`].
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+}
+
+fn follow_interpolated_targets(
+    time: Res<Time>,
+    fixed_time: Res<Time<Fixed>>,
+    config: Res<InterpolationConfig>,
+    enabled: Res<InterpolationEnabled>,
+    render_origin: Option<Res<RenderOrigin>>,
+    mut q_follower: Query<(&InterpolatedFollow, &mut Transform, Option<&Parent>)>,
+    q_target: Query<(
+        &Position,
+        &Rotation,
+        Option<&PreviousPosition>,
+        Option<&PreviousRotation>,
+        Option<&InterpolateTransformFields>,
+    )>,
+    q_global_transform: Query<&GlobalTransform>,
+) {
+    let alpha = rendered_alpha(&config, &fixed_time, &enabled);
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    let delta = time.delta_seconds();
+    for (follow, mut transform, maybe_parent) in &mut q_follower {
+        let Ok((
+            position,
+            rotation,
+            maybe_previous_position,
+            maybe_previous_rotation,
+            maybe_interpolate_transform_fields,
+        )) = q_target.get(follow.target)
+        else {
+            // The target was despawned or is not a rigid body.
+            continue;
+        };
+        // Static bodies have no previous transform, so they are rendered at their current one.
+        let previous_position = maybe_previous_position
+            .copied()
+            .unwrap_or((*position).into());
+        let previous_rotation = maybe_previous_rotation
+            .copied()
+            .unwrap_or((*rotation).into());
+        let (maybe_target_translation, _) = interpolated_pose(
+            alpha,
+            render_origin,
+            maybe_interpolate_transform_fields
+                .copied()
+                .unwrap_or_default(),
+            (position, rotation),
+            (&previous_position, &previous_rotation),
+        );
+        // The translation of the target is not interpolated, so it is rendered wherever its `Transform` puts it.
+        let Some(target_translation) = maybe_target_translation.or_else(|| {
+            q_global_transform
+                .get(follow.target)
+                .ok()
+                .map(GlobalTransform::translation)
+        }) else {
+            continue;
+        };
+
+        #[cfg(feature = "3d")]
+        let destination = target_translation + follow.offset;
+        #[cfg(feature = "2d")]
+        let destination = (target_translation.truncate() + follow.offset).extend(0.0);
+        let maybe_parent_global_transform =
+            maybe_parent.and_then(|parent| q_global_transform.get(parent.get()).ok());
+        let (Some(destination), _) = relative_to_parent_global_transform(
+            Some(destination),
+            None,
+            maybe_parent_global_transform,
+        ) else {
+            continue;
+        };
+        #[cfg(feature = "2d")]
+        let destination = destination.truncate().extend(transform.translation.z);
+
+        let translation = if follow.smoothing > 0.0 {
+            let t = 1.0 - 0.5_f32.powf(delta / follow.smoothing);
+            transform.translation.lerp(destination, t)
+        } else {
+            destination
+        };
+        transform.translation = translation;
+    }
+}
@@ -1,94 +1,406 @@
-use bevy::app::RunFixedMainLoop;
+#[cfg(feature = "2d")]
+use avian::math::Quaternion;
+use avian::math::{AdjustPrecision as _, AsF32 as _, Scalar, Vector};
+use bevy::{
+    app::RunFixedMainLoop,
+    ecs::{entity::EntityHashMap, system::SystemParam},
+    math::Mat3A,
+};
 
 use crate::prelude::*;
-use crate::previous_transform::{PreviousPosition, PreviousRotation};
+use crate::snap::{snap_rigid_body_type_changes, snap_teleported_bodies};
+use crate::visual_proxy::VisualProxies;
 
 pub(super) fn plugin(app: &mut App) {
+    app.configure_sets(
+        RunFixedMainLoop,
+        (InterpolateStep::Snap, InterpolateStep::Write)
+            .chain()
+            .in_set(AvianInterpolationVariableSystem::Interpolate),
+    );
+    app.add_systems(
+        RunFixedMainLoop,
+        (
+            (wake_bodies_on_render_origin_shift, wake_settled_bodies),
+            // Snapping has to happen after waking up, as it removes the difference between the
+            // previous and current physics transform that waking up relies on.
+            (snap_teleported_bodies, snap_rigid_body_type_changes),
+        )
+            .chain()
+            .in_set(InterpolateStep::Snap),
+    );
     app.add_systems(
         RunFixedMainLoop,
-        interpolate_rigid_bodies.in_set(AvianInterpolationVariableSystem::Interpolate),
+        interpolate_rigid_bodies.in_set(InterpolateStep::Write),
     );
 }
 
-fn interpolate_rigid_bodies(
+/// The steps of [`AvianInterpolationVariableSystem::Interpolate`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub(crate) enum InterpolateStep {
+    /// Wakes up settled bodies and snaps the ones that should not be interpolated this frame
+    /// by updating their previous physics transform.
+    Snap,
+    /// Reads the previous physics transforms to write the interpolated values.
+    Write,
+}
+
+/// Marks a rigid body whose previous and current physics transforms are identical, e.g. because it is [`Sleeping`],
+/// and whose [`Transform`] was already set to its physics transform.
+/// Such bodies are skipped by the interpolation until they move again.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub(crate) struct Settled;
+
+type InterpolantQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        Option<&'static Parent>,
+        &'static Position,
+        &'static Rotation,
+        &'static PreviousPosition,
+        &'static PreviousRotation,
+        Option<&'static InterpolateTransformFields>,
+        Has<VisualProxies>,
+    ),
+    Without<Settled>,
+>;
+
+pub(crate) fn interpolate_rigid_bodies(
     fixed_time: Res<Time<Fixed>>,
-    mut q_interpolant: Query<(
-        &mut Transform,
-        Option<&Parent>,
-        &Position,
-        &Rotation,
-        &PreviousPosition,
-        &PreviousRotation,
-        Option<&InterpolateTransformFields>,
-    )>,
-    q_global_transform: Query<&GlobalTransform>,
+    config: Res<InterpolationConfig>,
+    mut writer: TransformWriter,
 ) {
-    // The overstep fraction is a value between 0 and 1 that tells us how far we are between two fixed timesteps.
-    let alpha = fixed_time.overstep_fraction();
-
-    for (
-        mut transform,
-        maybe_parent,
-        position,
-        rotation,
-        previous_position,
-        previous_rotation,
-        maybe_interpolate_transform_fields,
-    ) in &mut q_interpolant
-    {
-        let interpolate_transform_fields = maybe_interpolate_transform_fields
-            .copied()
-            .unwrap_or_default();
-        let translation = match interpolate_transform_fields.translation {
-            InterpolationMode::Linear => Some(previous_position.lerp(position.0, alpha)),
-            InterpolationMode::Last => Some(position.0),
-            InterpolationMode::None => None,
-        };
-        #[cfg(feature = "2d")]
-        let translation = translation.map(|translation| translation.extend(0.));
+    writer.write(config.main_world_alpha(&fixed_time));
+}
 
-        let rotation = {
-            #[cfg(feature = "2d")]
-            {
-                Quat::from(*rotation)
+/// Sets the [`Transform`] of all interpolated rigid bodies to their current physics transform,
+/// i.e. the end of the interpolation.
+pub(crate) fn sync_transforms_to_physics(mut writer: TransformWriter) {
+    writer.write(1.0);
+}
+
+/// Removes [`Settled`] from all rigid bodies, so that they are interpolated again.
+pub(crate) fn wake_all_settled_bodies(
+    mut commands: Commands,
+    q_settled: Query<Entity, With<Settled>>,
+) {
+    for entity in &q_settled {
+        commands.entity(entity).remove::<Settled>();
+    }
+}
+
+fn wake_bodies_on_render_origin_shift(
+    mut commands: Commands,
+    q_settled: Query<Entity, With<Settled>>,
+    render_origin: Option<Res<RenderOrigin>>,
+    mut last_render_origin: Local<Vector>,
+) {
+    // Moving the origin moves every body in render space.
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    if render_origin == *last_render_origin {
+        return;
+    }
+    *last_render_origin = render_origin;
+    for entity in &q_settled {
+        commands.entity(entity).remove::<Settled>();
+    }
+}
+
+fn wake_settled_bodies(
+    mut commands: Commands,
+    q_moved: Query<
+        (
+            Entity,
+            &Position,
+            &Rotation,
+            &PreviousPosition,
+            &PreviousRotation,
+        ),
+        (
+            With<Settled>,
+            Or<(
+                Changed<Position>,
+                Changed<Rotation>,
+                Changed<PreviousPosition>,
+                Changed<PreviousRotation>,
+            )>,
+        ),
+    >,
+    q_reconfigured: Query<Entity, (With<Settled>, Changed<InterpolateTransformFields>)>,
+    q_parented: Query<(Entity, Ref<Parent>), With<Settled>>,
+    q_changed_global_transform: Query<(), Changed<GlobalTransform>>,
+) {
+    // Avian writes to the physics transform of awake bodies every step, even if they did not move,
+    // so we only wake up bodies that actually moved.
+    let moved = q_moved
+        .iter()
+        .filter(
+            |(_, position, rotation, previous_position, previous_rotation)| {
+                !is_at_rest(position, rotation, previous_position, previous_rotation)
+            },
+        )
+        .map(|(entity, ..)| entity);
+    let reparented = q_parented
+        .iter()
+        .filter(|(_, parent)| {
+            parent.is_changed() || q_changed_global_transform.contains(parent.get())
+        })
+        .map(|(entity, _)| entity);
+    for entity in moved.chain(&q_reconfigured).chain(reparented) {
+        commands.entity(entity).remove::<Settled>();
+    }
+}
+
+/// The decomposed [`GlobalTransform`]s of the parents of all interpolated bodies.
+/// Many bodies tend to share the same parent, e.g. a level root, so we only decompose each parent once per frame.
+#[derive(Debug, Default, Deref)]
+pub(crate) struct ParentTransforms(EntityHashMap<Transform>);
+
+impl ParentTransforms {
+    fn update(
+        &mut self,
+        q_interpolant: &InterpolantQuery,
+        q_global_transform: &Query<&GlobalTransform>,
+    ) {
+        self.0.clear();
+        for parent in q_interpolant
+            .iter()
+            .filter_map(|(_, _, maybe_parent, ..)| maybe_parent)
+        {
+            let parent = parent.get();
+            if self.0.contains_key(&parent) {
+                continue;
             }
-            #[cfg(feature = "3d")]
-            {
-                rotation.0
+            if let Ok(global_transform) = q_global_transform.get(parent) {
+                self.0.insert(parent, decompose(global_transform));
             }
-        };
-
-        let rotation = match interpolate_transform_fields.rotation {
-            InterpolationMode::Linear => Some(previous_rotation.slerp(rotation, alpha)),
-            InterpolationMode::Last => Some(rotation),
-            InterpolationMode::None => None,
-        };
-
-        let maybe_parent_transform = maybe_parent
-            .and_then(|parent| q_global_transform.get(parent.get()).ok())
-            .map(|global_transform| global_transform.compute_transform());
-
-        let new_translation = translation.map(|translation| {
-            maybe_parent_transform
-                .map(|parent_transform| translation - parent_transform.translation)
-                .unwrap_or(translation)
-        });
-
-        let new_rotation = rotation.map(|rotation| {
-            maybe_parent_transform
-                .map(|parent_transform| rotation * parent_transform.rotation.inverse())
-                .unwrap_or(rotation)
-        });
-
-        if let Some(translation) = new_translation {
-            if transform.translation.distance_squared(translation) > 1e-6 {
+        }
+    }
+}
+
+/// Same as [`GlobalTransform::compute_transform`], but skips the expensive decomposition for parents
+/// that are neither rotated nor scaled.
+pub(crate) fn decompose(global_transform: &GlobalTransform) -> Transform {
+    let affine = global_transform.affine();
+    if affine.matrix3 == Mat3A::IDENTITY {
+        Transform::from_translation(affine.translation.into())
+    } else {
+        global_transform.compute_transform()
+    }
+}
+
+fn is_at_rest(
+    position: &Position,
+    rotation: &Rotation,
+    previous_position: &PreviousPosition,
+    previous_rotation: &PreviousRotation,
+) -> bool {
+    previous_position.0 == position.0 && *previous_rotation == PreviousRotation::from(*rotation)
+}
+
+#[derive(SystemParam)]
+pub(crate) struct TransformWriter<'w, 's> {
+    config: Res<'w, InterpolationConfig>,
+    length_unit: Option<Res<'w, PhysicsLengthUnit>>,
+    render_origin: Option<Res<'w, RenderOrigin>>,
+    q_interpolant: InterpolantQuery<'w, 's>,
+    q_global_transform: Query<'w, 's, &'static GlobalTransform>,
+    par_commands: ParallelCommands<'w, 's>,
+    parent_transforms: Local<'s, ParentTransforms>,
+}
+
+impl TransformWriter<'_, '_> {
+    /// Writes the physics transform interpolated by `alpha` into the [`Transform`] of all interpolated rigid bodies.
+    fn write(&mut self, alpha: Scalar) {
+        let Self {
+            config,
+            length_unit,
+            render_origin,
+            q_interpolant,
+            q_global_transform,
+            par_commands,
+            parent_transforms,
+        } = self;
+        parent_transforms.update(q_interpolant, q_global_transform);
+        let parent_transforms = &**parent_transforms;
+        let par_commands = &*par_commands;
+        let render_origin = RenderOrigin::get(render_origin.as_deref());
+        let thresholds = WriteThresholds::new(config, length_unit.as_deref());
+        q_interpolant
+            .par_iter_mut()
+            .batching_strategy(config.batching_strategy())
+            .for_each(
+                |(
+                    entity,
+                    mut transform,
+                    maybe_parent,
+                    position,
+                    rotation,
+                    previous_position,
+                    previous_rotation,
+                    maybe_interpolate_transform_fields,
+                    has_visual_proxies,
+                )| {
+                    // Once there is nothing left to interpolate, the transform written below is final.
+                    if is_at_rest(position, rotation, previous_position, previous_rotation) {
+                        par_commands.command_scope(|mut commands| {
+                            commands.entity(entity).insert(Settled);
+                        });
+                    }
+
+                    // Bodies with visual proxies keep their physics transform, the proxies get the interpolated one.
+                    let alpha = if has_visual_proxies { 1.0 } else { alpha };
+                    let interpolate_transform_fields = maybe_interpolate_transform_fields
+                        .copied()
+                        .unwrap_or_default();
+                    let (translation, rotation) = interpolated_pose(
+                        alpha,
+                        render_origin,
+                        interpolate_transform_fields,
+                        (position, rotation),
+                        (previous_position, previous_rotation),
+                    );
+
+                    let maybe_parent_transform =
+                        maybe_parent.and_then(|parent| parent_transforms.get(&parent.get()));
+                    let (new_translation, new_rotation) =
+                        relative_to_parent(translation, rotation, maybe_parent_transform);
+                    thresholds.write(&mut transform, new_translation, new_rotation);
+                },
+            );
+    }
+}
+
+/// How much an interpolated pose has to differ from the current [`Transform`] to be written,
+/// as configured in [`InterpolationConfig`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WriteThresholds {
+    translation_squared: Scalar,
+    min_rotation_dot: f32,
+}
+
+impl WriteThresholds {
+    pub(crate) fn new(
+        config: &InterpolationConfig,
+        length_unit: Option<&PhysicsLengthUnit>,
+    ) -> Self {
+        Self {
+            translation_squared: config.translation_threshold(length_unit).powi(2),
+            min_rotation_dot: config.min_rotation_dot(),
+        }
+    }
+
+    /// Writes the fields of the pose that differ enough from the current [`Transform`],
+    /// so that change detection is not triggered for movements too small to see.
+    pub(crate) fn write(
+        &self,
+        transform: &mut Mut<Transform>,
+        translation: Option<Vec3>,
+        rotation: Option<Quat>,
+    ) {
+        if let Some(translation) = translation {
+            if transform
+                .translation
+                .distance_squared(translation)
+                .adjust_precision()
+                > self.translation_squared
+            {
                 transform.translation = translation;
             }
         }
-        if let Some(rotation) = new_rotation {
-            if transform.rotation.dot(rotation) < 0.9999 {
+        if let Some(rotation) = rotation {
+            // `q` and `-q` describe the same rotation, so we ignore the sign.
+            if transform.rotation.dot(rotation).abs() < self.min_rotation_dot {
                 transform.rotation = rotation;
             }
         }
     }
 }
+
+/// The pose of a rigid body in render space, `alpha` of the way between its previous and current physics transform.
+/// Fields that are not interpolated are `None`.
+pub(crate) fn interpolated_pose(
+    alpha: Scalar,
+    render_origin: Vector,
+    interpolate_transform_fields: InterpolateTransformFields,
+    (position, rotation): (&Position, &Rotation),
+    (previous_position, previous_rotation): (&PreviousPosition, &PreviousRotation),
+) -> (Option<Vec3>, Option<Quat>) {
+    let translation = match interpolate_transform_fields.translation {
+        InterpolationMode::Linear => Some(previous_position.lerp(position.0, alpha)),
+        InterpolationMode::Last => Some(position.0),
+        InterpolationMode::None => None,
+    };
+    // We interpolate in absolute physics space and only then move into render space,
+    // so shifting the origin between two physics steps does not smear the interpolation.
+    // This happens in the precision of the physics, but `Transform` always uses `f32`.
+    let translation = translation.map(|translation| (translation - render_origin).f32());
+    #[cfg(feature = "2d")]
+    let translation = translation.map(|translation| translation.extend(0.));
+
+    let rotation = {
+        #[cfg(feature = "2d")]
+        {
+            Quaternion::from(*rotation)
+        }
+        #[cfg(feature = "3d")]
+        {
+            rotation.0
+        }
+    };
+
+    let rotation = match interpolate_transform_fields.rotation {
+        InterpolationMode::Linear => Some(previous_rotation.slerp(rotation, alpha)),
+        InterpolationMode::Last => Some(rotation),
+        InterpolationMode::None => None,
+    };
+    let rotation = rotation.map(|rotation| rotation.f32());
+    (translation, rotation)
+}
+
+/// How far between their previous and current physics transform the rigid bodies are rendered in the main world.
+pub(crate) fn rendered_alpha(
+    config: &InterpolationConfig,
+    fixed_time: &Time<Fixed>,
+    enabled: &InterpolationEnabled,
+) -> Scalar {
+    // Without interpolation, the rigid bodies are rendered at their current physics pose.
+    if enabled.0 {
+        config.main_world_alpha(fixed_time)
+    } else {
+        1.0
+    }
+}
+
+/// Converts a pose in render space into the local space of a parent with the [`GlobalTransform`]
+/// `maybe_parent_global_transform`. Use [`relative_to_parent`] if the parent was already decomposed.
+pub(crate) fn relative_to_parent_global_transform(
+    translation: Option<Vec3>,
+    rotation: Option<Quat>,
+    maybe_parent_global_transform: Option<&GlobalTransform>,
+) -> (Option<Vec3>, Option<Quat>) {
+    let maybe_parent_transform = maybe_parent_global_transform.map(decompose);
+    relative_to_parent(translation, rotation, maybe_parent_transform.as_ref())
+}
+
+/// Converts a pose in render space into the local space of `maybe_parent_transform`.
+pub(crate) fn relative_to_parent(
+    translation: Option<Vec3>,
+    rotation: Option<Quat>,
+    maybe_parent_transform: Option<&Transform>,
+) -> (Option<Vec3>, Option<Quat>) {
+    let Some(parent_transform) = maybe_parent_transform else {
+        return (translation, rotation);
+    };
+    let inverse_parent_rotation = parent_transform.rotation.inverse();
+    (
+        translation.map(|translation| {
+            inverse_parent_rotation * (translation - parent_transform.translation)
+                / parent_transform.scale
+        }),
+        rotation.map(|rotation| inverse_parent_rotation * rotation),
+    )
+}
@@ -0,0 +1,109 @@
+use avian::math::{Quaternion, Scalar, Vector};
+use bevy::app::RunFixedMainLoop;
+
+use crate::{interpolate::InterpolateStep, prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<InterpolatedVelocity>();
+    app.add_systems(
+        RunFixedMainLoop,
+        update_interpolated_velocity.in_set(InterpolateStep::Write),
+    );
+}
+
+/// Opt-in for the velocity at which a rigid body moves on screen, e.g. for Doppler effects,
+/// motion blur, or blending locomotion animations.
+///
+/// Unlike [`LinearVelocity`] and [`AngularVelocity`], which can change abruptly on every physics step,
+/// this is derived from the two physics transforms that the rendered [`Transform`] is interpolated between,
+/// so it matches the motion you see. It is zero for fields using [`InterpolationMode::None`].
+///
+/// Insert it with its default value on any non-static rigid body. It is updated in [`AvianInterpolationVariableSystem::Interpolate`],
+/// after snapping, so a teleported body has no velocity instead of a huge spike.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct InterpolatedVelocity {
+    /// The rendered linear velocity in physics units per second.
+    pub linear: Vector,
+    /// The rendered angular velocity in radians per second.
+    #[cfg(feature = "2d")]
+    pub angular: Scalar,
+    /// The rendered angular velocity as a scaled axis in radians per second.
+    #[cfg(feature = "3d")]
+    pub angular: Vector,
+}
+
+fn update_interpolated_velocity(
+    fixed_time: Res<Time<Fixed>>,
+    mut q_velocity: Query<(
+        &mut InterpolatedVelocity,
+        &Position,
+        &Rotation,
+        &PreviousPosition,
+        &PreviousRotation,
+        Option<&InterpolateTransformFields>,
+    )>,
+) {
+    let timestep = fixed_time.timestep().as_secs_f64() as Scalar;
+    if timestep <= 0.0 {
+        return;
+    }
+    for (
+        mut velocity,
+        position,
+        rotation,
+        previous_position,
+        previous_rotation,
+        maybe_interpolate_transform_fields,
+    ) in &mut q_velocity
+    {
+        let interpolate_transform_fields = maybe_interpolate_transform_fields
+            .copied()
+            .unwrap_or_default();
+        let linear = match interpolate_transform_fields.translation {
+            InterpolationMode::None => Vector::ZERO,
+            _ => (position.0 - previous_position.0) / timestep,
+        };
+        let angular = match interpolate_transform_fields.rotation {
+            InterpolationMode::None => Default::default(),
+            _ => angular_velocity(previous_rotation.0, rotation, timestep),
+        };
+        velocity.set_if_neq(InterpolatedVelocity { linear, angular });
+    }
+}
+
+#[cfg(feature = "2d")]
+fn angular_velocity(
+    previous_rotation: Quaternion,
+    rotation: &Rotation,
+    timestep: Scalar,
+) -> Scalar {
+    let (axis, angle) =
+        shortest_arc(previous_rotation, Quaternion::from(*rotation)).to_axis_angle();
+    axis.z * angle / timestep
+}
+
+#[cfg(feature = "3d")]
+fn angular_velocity(
+    previous_rotation: Quaternion,
+    rotation: &Rotation,
+    timestep: Scalar,
+) -> Vector {
+    let (axis, angle) = shortest_arc(previous_rotation, rotation.0).to_axis_angle();
+    axis * angle / timestep
+}
+
+/// The rotation from `from` to `to`, taking the same path as the slerp between them.
+fn shortest_arc(from: Quaternion, to: Quaternion) -> Quaternion {
+    let delta = to * from.inverse();
+    if delta.w < 0.0 {
+        -delta
+    } else {
+        delta
+    }
+}
@@ -3,9 +3,13 @@
 #![doc = include_str!("../readme.md")]
 
 #[cfg(all(feature = "2d", feature = "3d"))]
-compile_error!("Cannot enable both 2d and 3d features at the same time.");
+compile_error!("Cannot enable both 2d and 3d features at the same time. To use both dimensions in one app, depend on both `avian_interpolation2d` and `avian_interpolation3d` instead.");
 #[cfg(all(not(feature = "2d"), not(feature = "3d")))]
 compile_error!("Cannot run without either 2d or 3d feature.");
+#[cfg(all(feature = "f32", feature = "f64"))]
+compile_error!("Cannot enable both f32 and f64 features at the same time.");
+#[cfg(all(not(feature = "f32"), not(feature = "f64")))]
+compile_error!("Cannot run without either f32 or f64 feature.");
 
 // This allows me to reference `avian` components in the docs without the annoying `cfg_attr`.
 #[allow(unused_imports)]
@@ -20,15 +24,62 @@ use bevy::{app::RunFixedMainLoop, prelude::*, time::run_fixed_main_schedule};
 /// Everything you need to interpolate transforms with Avian.
 pub mod prelude {
     pub(crate) use crate::avian::{self, prelude::*};
+    #[cfg(feature = "2d")]
+    pub use crate::AvianInterpolation2dPlugin;
+    #[cfg(feature = "3d")]
+    pub use crate::AvianInterpolation3dPlugin;
+    #[cfg(feature = "debug-plugin")]
+    pub use crate::{AvianInterpolationDebugPlugin, InterpolationGizmos};
+    pub use crate::{
+        AvianInterpolationDiagnosticsPlugin, AvianInterpolationPlugin, DriveFromTransform,
+        FixedStepsThisFrame, InterpolateLocalTransform, InterpolateTransformFields,
+        InterpolatedFollow, InterpolatedVelocity, InterpolationConfig, InterpolationEnabled,
+        InterpolationMode, InterpolationSnapped, InterpolationTiming, PreviousPosition,
+        PreviousRenderedTransform, PreviousRotation, RenderOrigin, SnapReason, VisualOf,
+    };
     pub(crate) use crate::{AvianInterpolationFixedSystem, AvianInterpolationVariableSystem};
-    pub use crate::{AvianInterpolationPlugin, InterpolateTransformFields, InterpolationMode};
     pub(crate) use bevy::prelude::*;
 }
 
+mod config;
+#[cfg(feature = "debug-plugin")]
+mod debug;
+mod diagnostics;
+mod drive_from_transform;
+mod extraction;
+mod fixed_steps;
+mod follow;
 mod interpolate;
+mod interpolated_velocity;
 mod lifecycle;
+mod local_transform;
 mod previous_transform;
+mod render_origin;
+mod rendered_transform;
+mod snap;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(test)]
+mod tests;
 mod transform_sync;
+mod validation;
+mod visual_proxy;
+
+pub use config::{InterpolationConfig, InterpolationTiming};
+#[cfg(feature = "debug-plugin")]
+pub use debug::{AvianInterpolationDebugPlugin, InterpolationGizmos};
+pub use diagnostics::AvianInterpolationDiagnosticsPlugin;
+pub use drive_from_transform::DriveFromTransform;
+pub use fixed_steps::FixedStepsThisFrame;
+pub use follow::InterpolatedFollow;
+pub use interpolated_velocity::InterpolatedVelocity;
+pub use local_transform::InterpolateLocalTransform;
+pub use previous_transform::{PreviousPosition, PreviousRotation};
+pub use render_origin::RenderOrigin;
+pub use rendered_transform::PreviousRenderedTransform;
+pub use snap::{InterpolationSnapped, SnapReason};
+pub use transform_sync::InterpolationEnabled;
+pub use visual_proxy::VisualOf;
 
 /// The plugin for [`Transform`] interpolation with Avian. Simply add it to your app after [`PhysicsPlugins`]:
 ///
@@ -49,18 +100,53 @@ mod transform_sync;
 ///
 /// That's already it! Now, all your rigid bodies will be interpolated.
 /// The interpolation source will be their [`Position`] and [`Rotation`].
-#[derive(Default)]
+#[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub struct AvianInterpolationPlugin;
+pub struct AvianInterpolationPlugin {
+    /// The configuration to insert as a resource.
+    pub config: InterpolationConfig,
+}
+
+/// [`AvianInterpolationPlugin`] under a name that makes the dimension explicit.
+/// Useful when using `avian_interpolation2d` and `avian_interpolation3d` in the same app.
+#[cfg(feature = "2d")]
+pub type AvianInterpolation2dPlugin = AvianInterpolationPlugin;
+
+/// [`AvianInterpolationPlugin`] under a name that makes the dimension explicit.
+/// Useful when using `avian_interpolation2d` and `avian_interpolation3d` in the same app.
+#[cfg(feature = "3d")]
+pub type AvianInterpolation3dPlugin = AvianInterpolationPlugin;
+
+impl AvianInterpolationPlugin {
+    /// Sets the [`InterpolationConfig`] to use.
+    pub fn with_config(mut self, config: InterpolationConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
 
 impl Plugin for AvianInterpolationPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<InterpolateTransformFields>();
+        app.register_type::<InterpolateTransformFields>()
+            .register_type::<InterpolationConfig>()
+            .register_type::<InterpolationTiming>();
+        app.insert_resource(self.config);
         app.add_plugins((
             previous_transform::plugin,
             interpolate::plugin,
+            interpolated_velocity::plugin,
+            drive_from_transform::plugin,
+            extraction::plugin,
+            fixed_steps::plugin,
+            follow::plugin,
             lifecycle::plugin,
+            local_transform::plugin,
+            render_origin::plugin,
+            rendered_transform::plugin,
+            snap::plugin,
             transform_sync::plugin,
+            validation::plugin,
+            visual_proxy::plugin,
         ));
         app.configure_sets(
             FixedPreUpdate,
@@ -71,16 +157,31 @@ impl Plugin for AvianInterpolationPlugin {
             )
                 .chain(),
         );
+        app.configure_sets(
+            FixedPreUpdate,
+            AvianInterpolationFixedSystem::CachePreviousPhysicsTransform
+                .run_if(resource_equals(InterpolationEnabled(true))),
+        );
         app.configure_sets(
             RunFixedMainLoop,
             (
                 AvianInterpolationVariableSystem::First,
                 AvianInterpolationVariableSystem::Interpolate,
+                AvianInterpolationVariableSystem::Follow,
                 AvianInterpolationVariableSystem::Last,
             )
                 .after(run_fixed_main_schedule)
                 .chain(),
         );
+        app.configure_sets(
+            RunFixedMainLoop,
+            AvianInterpolationVariableSystem::Interpolate
+                .run_if(resource_equals(InterpolationEnabled(true))),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        validation::validate_physics_schedule(app);
     }
 }
 
@@ -158,6 +259,9 @@ pub enum AvianInterpolationVariableSystem {
     First,
     /// Interpolate the transforms.
     Interpolate,
+    /// Move entities with [`InterpolatedFollow`] to the interpolated pose of their target.
+    /// Systems that need the rendered pose of rigid bodies this frame, e.g. custom camera controllers, can run here too.
+    Follow,
     /// The last system in the set. This is empty by default.
     Last,
 }
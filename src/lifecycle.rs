@@ -1,30 +1,167 @@
 use crate::{
+    interpolate::{interpolated_pose, relative_to_parent_global_transform},
     prelude::*,
-    previous_transform::{PreviousPosition, PreviousRotation},
 };
 
 pub(super) fn plugin(app: &mut App) {
-    app.observe(insert_previous_position)
+    // A body only becomes interpolated once it has all of these, no matter in which order they were added.
+    app.observe(insert_previous_transform::<Position>)
+        .observe(insert_previous_transform::<Rotation>)
+        .observe(insert_previous_transform::<RigidBody>)
         .observe(remove_previous_transform);
+    app.add_systems(
+        FixedPostUpdate,
+        start_from_initialized_pose
+            .after(PhysicsSet::Prepare)
+            .before(PhysicsSet::StepSimulation),
+    );
 }
 
-fn insert_previous_position(
-    trigger: Trigger<OnAdd, Position>,
-    mut commands: Commands,
-    q_position: Query<(&Position, &Rotation, &RigidBody)>,
-) {
-    let entity = trigger.entity();
-    let Ok((position, rotation, rigid_body)) = q_position.get(entity) else {
-        // This is a collider, not a rigid body.
+/// Marks bodies whose previous transform was inserted since the last physics step.
+/// Previous transforms that were loaded along with the body are not reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+struct NewlySpawned {
+    reset_position: bool,
+    reset_rotation: bool,
+}
+
+fn insert_previous_transform<C: Component>(trigger: Trigger<OnAdd, C>, mut commands: Commands) {
+    // Deferred so that all components of the spawned bundle are available.
+    commands
+        .entity(trigger.entity())
+        .add(initialize_previous_transform);
+}
+
+fn initialize_previous_transform(entity: Entity, world: &mut World) {
+    let Some(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let (Some(&position), Some(&rotation), Some(rigid_body)) = (
+        entity.get::<Position>(),
+        entity.get::<Rotation>(),
+        entity.get::<RigidBody>(),
+    ) else {
+        // This is a collider, not a rigid body, or the body is not fully spawned yet.
         return;
     };
     if rigid_body.is_static() {
         return;
     }
-    commands.entity(entity).insert((
-        PreviousPosition::from(*position),
-        PreviousRotation::from(*rotation),
-    ));
+    // When loading a scene, the previous transform may have been saved along with the body.
+    // Only the parts that were not saved start interpolating from the current transform.
+    let maybe_previous_position = entity.get::<PreviousPosition>().copied();
+    let maybe_previous_rotation = entity.get::<PreviousRotation>().copied();
+    if maybe_previous_position.is_some() && maybe_previous_rotation.is_some() {
+        return;
+    }
+    let previous_position =
+        maybe_previous_position.unwrap_or_else(|| PreviousPosition::from(position));
+    let previous_rotation =
+        maybe_previous_rotation.unwrap_or_else(|| PreviousRotation::from(rotation));
+    if maybe_previous_position.is_none() {
+        entity.insert(previous_position);
+    }
+    if maybe_previous_rotation.is_none() {
+        entity.insert(previous_rotation);
+    }
+    entity.insert(NewlySpawned {
+        reset_position: maybe_previous_position.is_none(),
+        reset_rotation: maybe_previous_rotation.is_none(),
+    });
+
+    // A body spawned with only a `Transform` gets its `Position` from it during the next physics step,
+    // so its `Transform` is already where it should be rendered.
+    if position != Position::default() || rotation != Rotation::default() {
+        render_at_physics_pose(
+            &mut entity,
+            (&position, &rotation),
+            (&previous_position, &previous_rotation),
+        );
+    }
+
+    let id = entity.id();
+    entity.world_scope(|world| {
+        world.send_event(InterpolationSnapped {
+            entity: id,
+            from: position.0,
+            to: position.0,
+            reason: SnapReason::Spawned,
+        });
+    });
+}
+
+/// Writes the physics pose into the [`Transform`] right away, as the body would otherwise be rendered
+/// at its spawn [`Transform`] until the next interpolation pass.
+fn render_at_physics_pose(
+    entity: &mut EntityWorldMut,
+    current: (&Position, &Rotation),
+    previous: (&PreviousPosition, &PreviousRotation),
+) {
+    let render_origin = RenderOrigin::get(entity.world().get_resource::<RenderOrigin>());
+    let interpolate_transform_fields = entity
+        .get::<InterpolateTransformFields>()
+        .copied()
+        .unwrap_or_default();
+    let maybe_parent_global_transform = entity
+        .get::<Parent>()
+        .and_then(|parent| entity.world().get::<GlobalTransform>(parent.get()))
+        .copied();
+    // Until the interpolation runs, the body is shown at its current physics pose.
+    let (translation, rotation) = interpolated_pose(
+        1.0,
+        render_origin,
+        interpolate_transform_fields,
+        current,
+        previous,
+    );
+    let (translation, rotation) = relative_to_parent_global_transform(
+        translation,
+        rotation,
+        maybe_parent_global_transform.as_ref(),
+    );
+    let Some(mut transform) = entity.get_mut::<Transform>() else {
+        return;
+    };
+    if let Some(translation) = translation {
+        transform.translation = translation;
+    }
+    if let Some(rotation) = rotation {
+        transform.rotation = rotation;
+    }
+    let transform = *transform;
+    // Transform propagation may already have run this frame.
+    if let Some(mut global_transform) = entity.get_mut::<GlobalTransform>() {
+        *global_transform = maybe_parent_global_transform.map_or_else(
+            || transform.into(),
+            |parent| parent.mul_transform(transform),
+        );
+    }
+}
+
+/// Physics may initialize the pose of a newly spawned body, e.g. from its [`Transform`],
+/// so we only start interpolating from the pose the body actually enters its first physics step with.
+fn start_from_initialized_pose(
+    mut commands: Commands,
+    mut q_spawned: Query<(
+        Entity,
+        &NewlySpawned,
+        &Position,
+        &Rotation,
+        &mut PreviousPosition,
+        &mut PreviousRotation,
+    )>,
+) {
+    for (entity, newly_spawned, position, rotation, mut previous_position, mut previous_rotation) in
+        &mut q_spawned
+    {
+        if newly_spawned.reset_position {
+            *previous_position = (*position).into();
+        }
+        if newly_spawned.reset_rotation {
+            *previous_rotation = (*rotation).into();
+        }
+        commands.entity(entity).remove::<NewlySpawned>();
+    }
 }
 
 fn remove_previous_transform(trigger: Trigger<OnRemove, Position>, mut commands: Commands) {
@@ -32,5 +169,5 @@ fn remove_previous_transform(trigger: Trigger<OnRemove, Position>, mut commands:
     // We assume that having `Rotation` without `Position` would be malformed, so we only do this check for `Position`.
     commands
         .entity(entity)
-        .remove::<(PreviousPosition, PreviousRotation)>();
+        .remove::<(PreviousPosition, PreviousRotation, NewlySpawned)>();
 }
@@ -0,0 +1,137 @@
+use bevy::app::RunFixedMainLoop;
+
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<InterpolateLocalTransform>();
+    app.observe(insert_local_transform_snapshot)
+        .observe(remove_local_transform_snapshot);
+    app.add_systems(
+        PreUpdate,
+        toggle_local_transform_interpolation.run_if(resource_changed::<InterpolationEnabled>),
+    );
+    app.add_systems(
+        FixedPreUpdate,
+        restore_local_transform
+            .in_set(AvianInterpolationFixedSystem::CachePreviousPhysicsTransform),
+    );
+    app.add_systems(
+        FixedPostUpdate,
+        capture_local_transform
+            .run_if(resource_equals(InterpolationEnabled(true)))
+            .before(PhysicsSet::Prepare),
+    );
+    app.add_systems(
+        RunFixedMainLoop,
+        interpolate_local_transform.in_set(AvianInterpolationVariableSystem::Interpolate),
+    );
+}
+
+/// Opt-in for entities whose local [`Transform`] is changed in [`FixedUpdate`], e.g. a child collider
+/// whose offset from its rigid body is animated, or a child mesh that bobs up and down.
+///
+/// Such entities are not rigid bodies, so they are not interpolated by default and would visibly snap
+/// to their new local [`Transform`] on every fixed step. With this component, their local [`Transform`]
+/// is interpolated between its values after the last two fixed steps, including the scale.
+/// Since the interpolation happens in local space, it composes with the interpolation of the parent rigid body.
+///
+/// Before [`FixedUpdate`] runs, the [`Transform`] is reset to the value it had after the last fixed step,
+/// so your fixed-time systems and the physics never see the interpolated value.
+/// Only write to the [`Transform`] in [`FixedUpdate`], as changes made in other schedules will be overwritten.
+/// When [`InterpolationEnabled`] is set to `false`, the [`Transform`] is reset to its value after the last fixed step.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq, Component, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct InterpolateLocalTransform;
+
+/// The local [`Transform`] after the last two fixed steps.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub(crate) struct LocalTransformSnapshot {
+    start: Transform,
+    end: Transform,
+}
+
+impl LocalTransformSnapshot {
+    /// The local [`Transform`] `alpha` of the way between the last two fixed steps.
+    pub(crate) fn interpolate(&self, alpha: f32) -> Transform {
+        let Self { start, end } = *self;
+        Transform {
+            translation: start.translation.lerp(end.translation, alpha),
+            rotation: start.rotation.slerp(end.rotation, alpha),
+            scale: start.scale.lerp(end.scale, alpha),
+        }
+    }
+}
+
+impl From<Transform> for LocalTransformSnapshot {
+    fn from(transform: Transform) -> Self {
+        Self {
+            start: transform,
+            end: transform,
+        }
+    }
+}
+
+fn insert_local_transform_snapshot(
+    trigger: Trigger<OnAdd, InterpolateLocalTransform>,
+    mut commands: Commands,
+    q_transform: Query<&Transform>,
+) {
+    let entity = trigger.entity();
+    let transform = q_transform.get(entity).copied().unwrap_or_default();
+    commands
+        .entity(entity)
+        .insert(LocalTransformSnapshot::from(transform));
+}
+
+fn remove_local_transform_snapshot(
+    trigger: Trigger<OnRemove, InterpolateLocalTransform>,
+    mut commands: Commands,
+) {
+    let entity = trigger.entity();
+    commands.entity(entity).remove::<LocalTransformSnapshot>();
+}
+
+fn restore_local_transform(mut q_local: Query<(&mut Transform, &mut LocalTransformSnapshot)>) {
+    for (mut transform, mut snapshot) in &mut q_local {
+        snapshot.start = snapshot.end;
+        transform.set_if_neq(snapshot.end);
+    }
+}
+
+/// While interpolation is disabled, the snapshot is not maintained, so the [`Transform`] must not be left
+/// at an interpolated value when disabling, and the snapshot must not be stale when enabling again.
+fn toggle_local_transform_interpolation(
+    interpolation_enabled: Res<InterpolationEnabled>,
+    mut q_local: Query<(&mut Transform, &mut LocalTransformSnapshot)>,
+) {
+    for (mut transform, mut snapshot) in &mut q_local {
+        if interpolation_enabled.0 {
+            *snapshot = LocalTransformSnapshot::from(*transform);
+        } else {
+            transform.set_if_neq(snapshot.end);
+        }
+    }
+}
+
+fn capture_local_transform(mut q_local: Query<(&Transform, &mut LocalTransformSnapshot)>) {
+    for (transform, mut snapshot) in &mut q_local {
+        snapshot.end = *transform;
+    }
+}
+
+fn interpolate_local_transform(
+    fixed_time: Res<Time<Fixed>>,
+    config: Res<InterpolationConfig>,
+    mut q_local: Query<(&mut Transform, &LocalTransformSnapshot)>,
+) {
+    let alpha = config.main_world_alpha(&fixed_time) as f32;
+    for (mut transform, snapshot) in &mut q_local {
+        // Avoid triggering change detection for children that are not animated.
+        transform.set_if_neq(snapshot.interpolate(alpha));
+    }
+}
@@ -2,6 +2,8 @@ use crate::prelude::*;
 use avian::math::{Quaternion, Vector};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PreviousPosition>();
+    app.register_type::<PreviousRotation>();
     app.add_systems(
         FixedPreUpdate,
         cache_previous_transform
@@ -9,8 +11,18 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Deref, DerefMut)]
-pub(crate) struct PreviousPosition(pub Vector);
+/// The [`Position`] of a rigid body before the last physics step.
+/// The rendered translation is interpolated between this and the current [`Position`].
+///
+/// This is inserted automatically for all non-static rigid bodies and updated right before every physics step.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Reflect, Deref, DerefMut)]
+#[reflect(Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PreviousPosition(pub Vector);
 
 impl From<Position> for PreviousPosition {
     fn from(value: Position) -> Self {
@@ -18,8 +30,18 @@ impl From<Position> for PreviousPosition {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Deref, DerefMut)]
-pub(crate) struct PreviousRotation(pub Quaternion);
+/// The [`Rotation`] of a rigid body before the last physics step.
+/// The rendered rotation is interpolated between this and the current [`Rotation`].
+///
+/// This is inserted automatically for all non-static rigid bodies and updated right before every physics step.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Reflect, Deref, DerefMut)]
+#[reflect(Component, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct PreviousRotation(pub Quaternion);
 
 impl From<Rotation> for PreviousRotation {
     fn from(value: Rotation) -> Self {
@@ -27,7 +49,8 @@ impl From<Rotation> for PreviousRotation {
     }
 }
 
-fn cache_previous_transform(
+pub(crate) fn cache_previous_transform(
+    config: Res<InterpolationConfig>,
     mut q_physics: Query<
         (
             Ref<Position>,
@@ -38,12 +61,17 @@ fn cache_previous_transform(
         Or<(Changed<Position>, Changed<Rotation>)>,
     >,
 ) {
-    for (position, rotation, mut previous_position, mut previous_rotation) in &mut q_physics {
-        if position.is_changed() {
-            *previous_position = (*position).into();
-        }
-        if rotation.is_changed() {
-            *previous_rotation = (*rotation).into();
-        }
-    }
+    q_physics
+        .par_iter_mut()
+        .batching_strategy(config.batching_strategy())
+        .for_each(
+            |(position, rotation, mut previous_position, mut previous_rotation)| {
+                if position.is_changed() {
+                    *previous_position = (*position).into();
+                }
+                if rotation.is_changed() {
+                    *previous_rotation = (*rotation).into();
+                }
+            },
+        );
 }
@@ -0,0 +1,25 @@
+use avian::math::Vector;
+
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<RenderOrigin>();
+}
+
+/// The physics position that is rendered at the origin of the world, for games that use a floating origin.
+///
+/// When this resource exists, it is subtracted from the interpolated [`Position`] of every rigid body
+/// before writing it into its [`Transform`], so that the [`Transform`]s stay small and precise even in huge worlds.
+/// Conversely, [`DriveFromTransform`] adds it back when copying a [`Transform`] into the [`Position`].
+///
+/// The interpolation itself happens in physics space, so you can move the origin at any time
+/// without bodies smearing across the shift. Without this resource, the origin is at [`Vector::ZERO`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Resource, Reflect, Deref, DerefMut)]
+#[reflect(Resource, Default, PartialEq)]
+pub struct RenderOrigin(pub Vector);
+
+impl RenderOrigin {
+    pub(crate) fn get(render_origin: Option<&Self>) -> Vector {
+        render_origin.map_or(Vector::ZERO, |render_origin| render_origin.0)
+    }
+}
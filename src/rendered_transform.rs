@@ -0,0 +1,61 @@
+use crate::{
+    extraction::{interpolate_global_transforms, InterpolateGlobalTransforms},
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PreviousRenderedTransform>();
+    app.observe(insert_rendered_transform)
+        .observe(remove_rendered_transform);
+    app.add_systems(
+        InterpolateGlobalTransforms,
+        update_previous_rendered_transform.after(interpolate_global_transforms),
+    );
+}
+
+/// Opt-in for the [`GlobalTransform`] an entity was rendered with on the previous frame,
+/// e.g. for computing per-object motion vectors for motion blur or TAA.
+///
+/// Since the interpolation overwrites the [`Transform`] in place, the previously rendered value would otherwise be lost.
+/// This is updated after [`Last`], right before rendering, and takes [`InterpolationTiming::Extraction`] into account.
+/// It is in world space, so it also works for children of rigid bodies and for entities with [`VisualOf`].
+/// On the frame it is inserted, it holds the current [`GlobalTransform`], so new entities start without motion.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component, Reflect, Deref)]
+#[reflect(Component, Default, PartialEq)]
+pub struct PreviousRenderedTransform(pub GlobalTransform);
+
+/// The [`GlobalTransform`] an entity was rendered with on the current frame,
+/// which becomes the [`PreviousRenderedTransform`] on the next one.
+/// This is `None` until the entity is rendered for the first time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Component)]
+struct RenderedTransform(Option<GlobalTransform>);
+
+fn insert_rendered_transform(
+    trigger: Trigger<OnAdd, PreviousRenderedTransform>,
+    mut commands: Commands,
+) {
+    let entity = trigger.entity();
+    commands.entity(entity).insert(RenderedTransform::default());
+}
+
+fn remove_rendered_transform(
+    trigger: Trigger<OnRemove, PreviousRenderedTransform>,
+    mut commands: Commands,
+) {
+    let entity = trigger.entity();
+    commands.entity(entity).remove::<RenderedTransform>();
+}
+
+fn update_previous_rendered_transform(
+    mut q_rendered: Query<(
+        &GlobalTransform,
+        &mut PreviousRenderedTransform,
+        &mut RenderedTransform,
+    )>,
+) {
+    for (global_transform, mut previous, mut rendered) in &mut q_rendered {
+        let previous_global_transform = rendered.0.unwrap_or(*global_transform);
+        previous.set_if_neq(PreviousRenderedTransform(previous_global_transform));
+        rendered.0 = Some(*global_transform);
+    }
+}
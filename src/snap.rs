@@ -0,0 +1,124 @@
+use avian::math::Vector;
+
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<InterpolationSnapped>();
+    app.register_type::<SnapReason>();
+    app.add_event::<InterpolationSnapped>();
+}
+
+/// Sent whenever a rigid body is snapped to its current physics transform instead of being interpolated,
+/// which shows up as a visible pop. Useful for hiding the pop, e.g. with a particle effect,
+/// or for logging unintended teleports.
+#[derive(Debug, Clone, Copy, PartialEq, Event, Reflect)]
+#[reflect(PartialEq)]
+pub struct InterpolationSnapped {
+    /// The rigid body that was snapped.
+    pub entity: Entity,
+    /// The physics position the interpolation would have started from.
+    pub from: Vector,
+    /// The physics position the rigid body was snapped to.
+    pub to: Vector,
+    /// Why the rigid body was snapped.
+    pub reason: SnapReason,
+}
+
+/// Why a rigid body was snapped, see [`InterpolationSnapped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(PartialEq, Hash)]
+#[non_exhaustive]
+pub enum SnapReason {
+    /// The rigid body was just spawned, so there is nothing to interpolate from yet.
+    /// In this case, [`InterpolationSnapped::from`] is the same as [`InterpolationSnapped::to`].
+    Spawned,
+    /// The rigid body moved further than [`InterpolationConfig::teleport_threshold`] in a single physics step.
+    Teleported,
+    /// The [`RigidBody`] type of the rigid body changed.
+    RigidBodyChanged,
+    /// More fixed steps were due this frame than [`InterpolationConfig::max_fixed_steps_per_frame`] allows.
+    FixedStepsCapped,
+}
+
+/// Snaps bodies that moved too far to be interpolated, e.g. because their [`Position`] was set to a spawn point.
+pub(crate) fn snap_teleported_bodies(
+    config: Res<InterpolationConfig>,
+    mut q_moved: Query<
+        (
+            Entity,
+            &Position,
+            &Rotation,
+            &mut PreviousPosition,
+            &mut PreviousRotation,
+        ),
+        Changed<Position>,
+    >,
+    mut snapped: EventWriter<InterpolationSnapped>,
+) {
+    let Some(teleport_threshold) = config.teleport_threshold else {
+        return;
+    };
+    for (entity, position, rotation, mut previous_position, mut previous_rotation) in &mut q_moved {
+        if previous_position.distance_squared(position.0) <= teleport_threshold.powi(2) {
+            continue;
+        }
+        snapped.send(InterpolationSnapped {
+            entity,
+            from: previous_position.0,
+            to: position.0,
+            reason: SnapReason::Teleported,
+        });
+        *previous_position = (*position).into();
+        *previous_rotation = (*rotation).into();
+    }
+}
+
+/// Snaps bodies whose [`RigidBody`] type changed, as e.g. a static body turning dynamic has no
+/// meaningful previous transform to interpolate from.
+pub(crate) fn snap_rigid_body_type_changes(
+    mut commands: Commands,
+    mut q_changed: Query<
+        (
+            Entity,
+            &RigidBody,
+            &Position,
+            &Rotation,
+            Option<&mut PreviousPosition>,
+            Option<&mut PreviousRotation>,
+        ),
+        Changed<RigidBody>,
+    >,
+    q_added: Query<(), Added<RigidBody>>,
+    mut snapped: EventWriter<InterpolationSnapped>,
+) {
+    for (entity, rigid_body, position, rotation, previous_position, previous_rotation) in
+        &mut q_changed
+    {
+        if q_added.contains(entity) {
+            // Newly spawned bodies are handled when inserting their previous transform.
+            continue;
+        }
+        let from = previous_position
+            .as_deref()
+            .map_or(position.0, |previous_position| previous_position.0);
+        match (previous_position, previous_rotation) {
+            (Some(mut previous_position), Some(mut previous_rotation)) => {
+                *previous_position = (*position).into();
+                *previous_rotation = (*rotation).into();
+            }
+            _ if rigid_body.is_static() => continue,
+            _ => {
+                commands.entity(entity).insert((
+                    PreviousPosition::from(*position),
+                    PreviousRotation::from(*rotation),
+                ));
+            }
+        }
+        snapped.send(InterpolationSnapped {
+            entity,
+            from,
+            to: position.0,
+            reason: SnapReason::RigidBodyChanged,
+        });
+    }
+}
@@ -0,0 +1,200 @@
+//! Utilities for testing [`AvianInterpolationPlugin`] in a headless [`App`] with deterministic time stepping.
+//! Enable the `testing` feature to use them in your own tests.
+//!
+//! ```rust,no_run
+#![cfg_attr(feature = "2d", doc = "use avian2d::{math::Vector, prelude::*};")]
+#![cfg_attr(feature = "3d", doc = "use avian3d::{math::Vector, prelude::*};")]
+#![cfg_attr(
+    feature = "2d",
+    doc = "use avian_interpolation2d::testing::{create_app, InterpolationTestAppExt as _};"
+)]
+#![cfg_attr(
+    feature = "3d",
+    doc = "use avian_interpolation3d::testing::{create_app, InterpolationTestAppExt as _};"
+)]
+//!
+//! let mut app = create_app();
+//! let entity = app
+//!     .world_mut()
+//!     .spawn((RigidBody::Kinematic, Position::default(), Rotation::default()))
+//!     .id();
+//! app.advance_fixed_steps(1);
+//! app.advance_to_overstep(0.5);
+//! app.assert_rendered_translation(entity, Vector::ZERO);
+//! ```
+
+use std::time::Duration;
+
+#[cfg(feature = "2d")]
+use avian::math::Quaternion;
+use avian::math::{AsF32 as _, Vector};
+use bevy::{
+    ecs::system::SystemId, render::mesh::Mesh, scene::ScenePlugin, time::TimeUpdateStrategy,
+};
+
+use crate::{
+    interpolate::interpolate_rigid_bodies, prelude::*, previous_transform::cache_previous_transform,
+};
+
+/// How far the rendered translation may be from the expected one in the assertions of [`InterpolationTestAppExt`].
+pub const TRANSLATION_TOLERANCE: f32 = 1e-2;
+
+/// How far the rendered rotation may be from the expected one in the assertions of [`InterpolationTestAppExt`], in radians.
+pub const ROTATION_TOLERANCE: f32 = 1e-2;
+
+/// Creates a headless [`App`] with [`PhysicsPlugins`] and [`AvianInterpolationPlugin`].
+///
+/// Time only advances through the methods of [`InterpolationTestAppExt`], so every test runs the same way,
+/// no matter how fast the machine running it is. The app has already been updated once, so that time starts counting.
+pub fn create_app() -> App {
+    create_app_with(|_| {})
+}
+
+/// Like [`create_app`], but calls `setup` before the first update, e.g. to add [`Startup`] systems.
+pub fn create_app_with(setup: impl FnOnce(&mut App)) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        ScenePlugin,
+        PhysicsPlugins::default(),
+        AvianInterpolationPlugin::default(),
+    ))
+    .init_asset::<Mesh>()
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    let isolated_systems = IsolatedSystems {
+        cache_previous_transform: app.world_mut().register_system(cache_previous_transform),
+        interpolate_rigid_bodies: app.world_mut().register_system(interpolate_rigid_bodies),
+    };
+    app.insert_resource(isolated_systems);
+    setup(&mut app);
+    app.finish();
+    app.cleanup();
+    // The first update only initializes the clocks and does not advance them.
+    app.update();
+    app
+}
+
+/// The per-entity systems of the interpolation, registered on their own so that they can run without the rest
+/// of their schedule, see [`InterpolationTestAppExt::run_cache_previous_transform`].
+#[derive(Debug, Clone, Copy, Resource)]
+struct IsolatedSystems {
+    cache_previous_transform: SystemId,
+    interpolate_rigid_bodies: SystemId,
+}
+
+/// Extension methods for driving an [`App`] created by [`create_app`].
+pub trait InterpolationTestAppExt {
+    /// The timestep of [`Time<Fixed>`].
+    fn fixed_timestep(&self) -> Duration;
+
+    /// Advances the time by `duration` and runs a single update.
+    /// This will run as many fixed steps as fit into the accumulated time.
+    fn advance_time(&mut self, duration: Duration);
+
+    /// Runs a single update that runs exactly `steps` fixed steps, keeping the current overstep.
+    fn advance_fixed_steps(&mut self, steps: u32);
+
+    /// Runs a single update that advances the time just enough for [`Time::overstep_fraction`]
+    /// to become `overstep_fraction`. Runs at most a single fixed step.
+    fn advance_to_overstep(&mut self, overstep_fraction: f32);
+
+    /// Runs only the system that caches the [`PreviousPosition`] and [`PreviousRotation`] before every physics step,
+    /// without the rest of [`FixedPreUpdate`], e.g. to benchmark it.
+    fn run_cache_previous_transform(&mut self);
+
+    /// Runs only the system that writes the interpolated [`Transform`]s of the rigid bodies,
+    /// without the rest of the fixed main loop, e.g. to benchmark it.
+    fn run_interpolate_rigid_bodies(&mut self);
+
+    /// Asserts that the rendered [`GlobalTransform`] of `entity` is at the physics position `expected`,
+    /// taking the [`RenderOrigin`] into account.
+    fn assert_rendered_translation(&self, entity: Entity, expected: Vector);
+
+    /// Asserts that the rendered [`GlobalTransform`] of `entity` has the physics rotation `expected`.
+    fn assert_rendered_rotation(&self, entity: Entity, expected: Rotation);
+}
+
+impl InterpolationTestAppExt for App {
+    fn fixed_timestep(&self) -> Duration {
+        self.world().resource::<Time<Fixed>>().timestep()
+    }
+
+    fn advance_time(&mut self, duration: Duration) {
+        self.insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+        self.update();
+    }
+
+    fn advance_fixed_steps(&mut self, steps: u32) {
+        let duration = self.fixed_timestep() * steps;
+        self.advance_time(duration);
+    }
+
+    fn advance_to_overstep(&mut self, overstep_fraction: f32) {
+        let timestep = self.fixed_timestep();
+        let overstep = self.world().resource::<Time<Fixed>>().overstep();
+        let target_overstep = timestep.mul_f32(overstep_fraction.clamp(0.0, 1.0));
+        let duration = if target_overstep >= overstep {
+            target_overstep - overstep
+        } else {
+            timestep - overstep + target_overstep
+        };
+        self.advance_time(duration);
+    }
+
+    fn run_cache_previous_transform(&mut self) {
+        let system = self
+            .world()
+            .resource::<IsolatedSystems>()
+            .cache_previous_transform;
+        self.world_mut()
+            .run_system(system)
+            .expect("the system is registered in `create_app`");
+    }
+
+    fn run_interpolate_rigid_bodies(&mut self) {
+        let system = self
+            .world()
+            .resource::<IsolatedSystems>()
+            .interpolate_rigid_bodies;
+        self.world_mut()
+            .run_system(system)
+            .expect("the system is registered in `create_app`");
+    }
+
+    #[track_caller]
+    fn assert_rendered_translation(&self, entity: Entity, expected: Vector) {
+        let actual = rendered_transform(self, entity).translation;
+        let render_origin = RenderOrigin::get(self.world().get_resource::<RenderOrigin>());
+        let expected = (expected - render_origin).f32();
+        #[cfg(feature = "2d")]
+        let expected = expected.extend(0.0);
+        assert!(
+            actual.distance(expected) <= TRANSLATION_TOLERANCE,
+            "Expected {entity} to be rendered at {expected}, but it was rendered at {actual}"
+        );
+    }
+
+    #[track_caller]
+    fn assert_rendered_rotation(&self, entity: Entity, expected: Rotation) {
+        let actual = rendered_transform(self, entity).rotation;
+        #[cfg(feature = "2d")]
+        let expected = Quaternion::from(expected).f32();
+        #[cfg(feature = "3d")]
+        let expected = expected.0.f32();
+        assert!(
+            actual.angle_between(expected) <= ROTATION_TOLERANCE,
+            "Expected {entity} to be rendered with rotation {expected}, but it was rendered with {actual}"
+        );
+    }
+}
+
+#[track_caller]
+fn rendered_transform(app: &App, entity: Entity) -> Transform {
+    app.world()
+        .get::<GlobalTransform>(entity)
+        .unwrap_or_else(|| panic!("{entity} has no `GlobalTransform`"))
+        .compute_transform()
+}
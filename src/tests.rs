@@ -0,0 +1,1106 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use avian::math::{AsF32 as _, Scalar, Vector};
+use bevy::{ecs::entity::EntityHashMap, scene::DynamicSceneBuilder, utils::Instant};
+
+use crate::{
+    extraction::RestoreGlobalTransforms,
+    interpolate::Settled,
+    prelude::*,
+    testing::{create_app, create_app_with, InterpolationTestAppExt as _},
+    validation,
+};
+
+/// Fast enough to move exactly one unit per fixed step at the default timestep of 64 Hz.
+const SPEED: Scalar = 64.0;
+
+fn spawn_moving_body(app: &mut App, position: Vector) -> Entity {
+    app.world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            Position(position),
+            Rotation::default(),
+            LinearVelocity(Vector::X * SPEED),
+            TransformBundle::from_transform(Transform::from_translation(translation(position))),
+        ))
+        .id()
+}
+
+fn translation(position: Vector) -> Vec3 {
+    #[cfg(feature = "2d")]
+    {
+        position.f32().extend(0.0)
+    }
+    #[cfg(feature = "3d")]
+    {
+        position.f32()
+    }
+}
+
+/// Moves the real time ahead of the wall clock, so that no real time seems to pass during a frame.
+/// Otherwise, how far [`InterpolationTiming::Extraction`] interpolates depends on how fast the test runs.
+fn freeze_real_time(app: &mut App) {
+    let future = Instant::now() + Duration::from_secs(3600);
+    app.world_mut()
+        .resource_mut::<Time<Real>>()
+        .update_with_instant(future);
+}
+
+fn physics_position(app: &App, entity: Entity) -> Vector {
+    app.world().get::<Position>(entity).unwrap().0
+}
+
+fn distance_per_step(app: &App) -> Scalar {
+    SPEED * app.fixed_timestep().as_secs_f64() as Scalar
+}
+
+#[test]
+fn linear_renders_between_last_two_physics_steps() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.25);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(
+        entity,
+        position - Vector::X * distance_per_step(&app) * 0.75,
+    );
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn last_renders_last_physics_step() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(InterpolateTransformFields::from(InterpolationMode::Last));
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position);
+}
+
+#[test]
+fn none_leaves_transform_untouched() {
+    let mut app = create_app();
+    let start = Vector::X * 5.0;
+    let entity = spawn_moving_body(&mut app, start);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(InterpolateTransformFields::from(InterpolationMode::None));
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    assert_ne!(physics_position(&app, entity), start);
+    app.assert_rendered_translation(entity, start);
+}
+
+#[test]
+fn parented_body_is_rendered_at_interpolated_world_position() {
+    let mut app = create_app();
+    let parent_position = Vector::X * 10.0;
+    let parent = app
+        .world_mut()
+        .spawn(TransformBundle::from_transform(
+            Transform::from_translation(translation(parent_position)),
+        ))
+        .id();
+    let entity = spawn_moving_body(&mut app, parent_position);
+    app.world_mut().entity_mut(entity).set_parent(parent);
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn body_spawned_later_is_interpolated() {
+    let mut app = create_app();
+    app.advance_fixed_steps(3);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+    assert!(app.world().get::<PreviousPosition>(entity).is_some());
+    assert!(app.world().get::<PreviousRotation>(entity).is_some());
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn static_body_is_not_interpolated() {
+    let mut app = create_app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Static,
+            Position::default(),
+            Rotation::default(),
+            TransformBundle::default(),
+        ))
+        .id();
+    app.advance_fixed_steps(1);
+    assert!(app.world().get::<PreviousPosition>(entity).is_none());
+}
+
+#[test]
+fn removing_position_removes_previous_transform() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+
+    app.world_mut().entity_mut(entity).remove::<Position>();
+    app.advance_fixed_steps(1);
+    assert!(app.world().get::<PreviousPosition>(entity).is_none());
+    assert!(app.world().get::<PreviousRotation>(entity).is_none());
+}
+
+#[test]
+fn despawned_body_is_handled_gracefully() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+
+    app.world_mut().despawn(entity);
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+}
+
+#[test]
+fn body_at_rest_is_settled_until_it_moves() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+    assert!(app.world().get::<Settled>(entity).is_none());
+
+    app.world_mut().get_mut::<LinearVelocity>(entity).unwrap().0 = Vector::ZERO;
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+    assert!(app.world().get::<Settled>(entity).is_some());
+    app.assert_rendered_translation(entity, physics_position(&app, entity));
+
+    app.world_mut().get_mut::<LinearVelocity>(entity).unwrap().0 = Vector::X * SPEED;
+    app.advance_fixed_steps(2);
+    assert!(app.world().get::<Settled>(entity).is_none());
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn movement_below_translation_threshold_is_not_written() {
+    let mut app = create_app();
+    app.world_mut()
+        .resource_mut::<InterpolationConfig>()
+        .translation_threshold = Some(10.0);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    assert_ne!(physics_position(&app, entity), Vector::ZERO);
+    app.assert_rendered_translation(entity, Vector::ZERO);
+}
+
+#[test]
+fn render_origin_shift_does_not_smear_interpolation() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+
+    app.insert_resource(RenderOrigin(Vector::X * 1000.0));
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn render_origin_shift_moves_bodies_at_rest() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.world_mut().get_mut::<LinearVelocity>(entity).unwrap().0 = Vector::ZERO;
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+    assert!(app.world().get::<Settled>(entity).is_some());
+
+    app.insert_resource(RenderOrigin(Vector::X * 1000.0));
+    app.advance_to_overstep(0.75);
+    app.assert_rendered_translation(entity, Vector::ZERO);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn transform_write_to_settled_body_is_reported_and_kept_until_it_moves() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+    app.world_mut().get_mut::<LinearVelocity>(entity).unwrap().0 = Vector::ZERO;
+    app.advance_fixed_steps(2);
+    assert!(app.world().get::<Settled>(entity).is_some());
+
+    let written = Vector::X * 100.0;
+    app.world_mut()
+        .get_mut::<Transform>(entity)
+        .unwrap()
+        .translation = translation(written);
+    app.advance_to_overstep(0.5);
+    assert!(app
+        .world()
+        .resource::<validation::WarnedAboutTransformWrites>()
+        .contains(&entity));
+    app.assert_rendered_translation(entity, written);
+
+    app.world_mut().get_mut::<LinearVelocity>(entity).unwrap().0 = Vector::X * SPEED;
+    app.advance_fixed_steps(1);
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(
+        entity,
+        physics_position(&app, entity) - Vector::X * distance_per_step(&app) * 0.5,
+    );
+}
+
+#[test]
+fn local_transform_changed_in_fixed_update_is_interpolated() {
+    let mut app = create_app();
+    app.add_systems(
+        FixedUpdate,
+        |mut q_local: Query<&mut Transform, With<InterpolateLocalTransform>>| {
+            for mut transform in &mut q_local {
+                transform.translation.x += 1.0;
+            }
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn((TransformBundle::default(), InterpolateLocalTransform))
+        .id();
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let translation = app.world().get::<Transform>(entity).unwrap().translation;
+    assert!((translation.x - 1.5).abs() < 1e-3, "{translation}");
+}
+
+#[test]
+fn disabling_interpolation_resets_local_transform() {
+    let mut app = create_app();
+    app.add_systems(
+        FixedUpdate,
+        |mut q_local: Query<&mut Transform, With<InterpolateLocalTransform>>| {
+            for mut transform in &mut q_local {
+                transform.translation.x += 1.0;
+            }
+        },
+    );
+    let entity = app
+        .world_mut()
+        .spawn((TransformBundle::default(), InterpolateLocalTransform))
+        .id();
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+
+    app.insert_resource(InterpolationEnabled(false));
+    app.advance_time(Duration::ZERO);
+    let translation = app.world().get::<Transform>(entity).unwrap().translation;
+    assert!((translation.x - 2.0).abs() < 1e-3, "{translation}");
+
+    app.advance_fixed_steps(2);
+    let translation = app.world().get::<Transform>(entity).unwrap().translation;
+    assert!((translation.x - 4.0).abs() < 1e-3, "{translation}");
+
+    app.insert_resource(InterpolationEnabled(true));
+    app.advance_time(Duration::ZERO);
+    app.advance_fixed_steps(1);
+    app.advance_to_overstep(0.5);
+    let translation = app.world().get::<Transform>(entity).unwrap().translation;
+    assert!((translation.x - 4.5).abs() < 1e-3, "{translation}");
+}
+
+#[test]
+fn visual_proxy_is_interpolated_while_body_keeps_physics_transform() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    let proxy = app
+        .world_mut()
+        .spawn((TransformBundle::default(), VisualOf::new(entity)))
+        .id();
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position);
+    app.assert_rendered_translation(proxy, position - Vector::X * distance_per_step(&app) * 0.5);
+
+    app.world_mut().entity_mut(proxy).remove::<VisualOf>();
+    app.advance_to_overstep(0.75);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(
+        entity,
+        position - Vector::X * distance_per_step(&app) * 0.25,
+    );
+}
+
+#[test]
+fn visual_proxy_of_settled_body_is_not_written() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    let proxy = app
+        .world_mut()
+        .spawn((TransformBundle::default(), VisualOf::new(entity)))
+        .id();
+    app.advance_fixed_steps(1);
+
+    app.world_mut().get_mut::<LinearVelocity>(entity).unwrap().0 = Vector::ZERO;
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+    assert!(app.world().get::<Settled>(entity).is_some());
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(proxy, position);
+    let last_changed = app
+        .world()
+        .entity(proxy)
+        .get_ref::<Transform>()
+        .unwrap()
+        .last_changed();
+
+    app.advance_fixed_steps(1);
+    app.advance_to_overstep(0.75);
+    app.assert_rendered_translation(proxy, position);
+    let transform = app.world().entity(proxy).get_ref::<Transform>().unwrap();
+    assert_eq!(transform.last_changed(), last_changed);
+}
+
+#[test]
+fn visual_proxy_can_be_pointed_at_another_body() {
+    let mut app = create_app();
+    let first = spawn_moving_body(&mut app, Vector::ZERO);
+    let second = spawn_moving_body(&mut app, Vector::Y * 10.0);
+    let proxy = app
+        .world_mut()
+        .spawn((TransformBundle::default(), VisualOf::new(first)))
+        .id();
+    app.advance_fixed_steps(2);
+
+    app.world_mut()
+        .entity_mut(proxy)
+        .insert(VisualOf::new(second));
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, first);
+    app.assert_rendered_translation(first, position - Vector::X * distance_per_step(&app) * 0.5);
+    let position = physics_position(&app, second);
+    app.assert_rendered_translation(second, position);
+    app.assert_rendered_translation(proxy, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn entity_references_are_mapped_when_loading_a_scene() {
+    let mut app = create_app();
+    let body = spawn_moving_body(&mut app, Vector::ZERO);
+    let proxy = app
+        .world_mut()
+        .spawn((
+            TransformBundle::default(),
+            VisualOf::new(body),
+            InterpolatedFollow::new(body),
+        ))
+        .id();
+    let scene = DynamicSceneBuilder::from_world(app.world())
+        .deny_all()
+        .allow::<RigidBody>()
+        .allow::<Position>()
+        .allow::<Rotation>()
+        .allow::<LinearVelocity>()
+        .allow::<Transform>()
+        .allow::<GlobalTransform>()
+        .allow::<VisualOf>()
+        .allow::<InterpolatedFollow>()
+        .extract_entities([body, proxy].into_iter())
+        .build();
+    let mut entity_map = EntityHashMap::default();
+    scene
+        .write_to_world(app.world_mut(), &mut entity_map)
+        .unwrap();
+
+    let loaded_body = entity_map[&body];
+    let loaded_proxy = entity_map[&proxy];
+    assert_ne!(loaded_body, body);
+    assert_eq!(
+        app.world().get::<VisualOf>(loaded_proxy),
+        Some(&VisualOf::new(loaded_body))
+    );
+    assert_eq!(
+        app.world()
+            .get::<InterpolatedFollow>(loaded_proxy)
+            .map(|follow| follow.target),
+        Some(loaded_body)
+    );
+}
+
+#[test]
+fn visual_proxy_follows_body_while_interpolation_is_disabled() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    let proxy = app
+        .world_mut()
+        .spawn((TransformBundle::default(), VisualOf::new(entity)))
+        .id();
+    app.advance_fixed_steps(2);
+
+    app.insert_resource(InterpolationEnabled(false));
+    app.advance_fixed_steps(1);
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(proxy, position);
+}
+
+#[test]
+fn extraction_timing_only_interpolates_rendered_global_transform() {
+    let mut app = create_app();
+    app.world_mut().resource_mut::<InterpolationConfig>().timing = InterpolationTiming::Extraction;
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    let transform = app.world().get::<Transform>(entity).unwrap();
+    assert!(transform.translation.distance(translation(position)) < 1e-3);
+    // Some real time passes between the start of the frame and the extraction,
+    // so the rendered translation is somewhere between the overstep and the physics position.
+    let rendered = app
+        .world()
+        .get::<GlobalTransform>(entity)
+        .unwrap()
+        .translation();
+    let interpolated = translation(position - Vector::X * distance_per_step(&app) * 0.5);
+    assert!(rendered.x >= interpolated.x - 1e-3 && rendered.x <= translation(position).x + 1e-3);
+
+    // The main world gets its physics `GlobalTransform` back at the start of the next frame.
+    app.world_mut().run_schedule(RestoreGlobalTransforms);
+    let global_transform = app.world().get::<GlobalTransform>(entity).unwrap();
+    assert!(
+        global_transform
+            .translation()
+            .distance(translation(position))
+            < 1e-3
+    );
+}
+
+#[test]
+fn extraction_timing_is_not_visible_in_last() {
+    #[derive(Debug, Default, Resource)]
+    struct TranslationInLast(Vec3);
+
+    let mut app = create_app();
+    app.world_mut().resource_mut::<InterpolationConfig>().timing = InterpolationTiming::Extraction;
+    freeze_real_time(&mut app);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.init_resource::<TranslationInLast>().add_systems(
+        Last,
+        move |q_global_transform: Query<&GlobalTransform>,
+              mut translation_in_last: ResMut<TranslationInLast>| {
+            translation_in_last.0 = q_global_transform.get(entity).unwrap().translation();
+        },
+    );
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let position = physics_position(&app, entity);
+    let translation_in_last = app.world().resource::<TranslationInLast>().0;
+    assert!(translation_in_last.distance(translation(position)) < 1e-3);
+    app.assert_rendered_translation(entity, position - Vector::X * distance_per_step(&app) * 0.5);
+}
+
+#[test]
+fn extraction_timing_interpolates_local_transforms_and_followers() {
+    let mut app = create_app();
+    app.world_mut().resource_mut::<InterpolationConfig>().timing = InterpolationTiming::Extraction;
+    freeze_real_time(&mut app);
+    app.add_systems(
+        FixedUpdate,
+        |mut q_local: Query<&mut Transform, With<InterpolateLocalTransform>>| {
+            for mut transform in &mut q_local {
+                transform.translation.x += 1.0;
+            }
+        },
+    );
+    let local = app
+        .world_mut()
+        .spawn((TransformBundle::default(), InterpolateLocalTransform))
+        .id();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    let follower = app
+        .world_mut()
+        .spawn((TransformBundle::default(), InterpolatedFollow::new(entity)))
+        .id();
+    app.advance_fixed_steps(2);
+
+    app.advance_to_overstep(0.5);
+    let transform = app.world().get::<Transform>(local).unwrap();
+    assert!((transform.translation.x - 2.0).abs() < 1e-3);
+    let rendered = app
+        .world()
+        .get::<GlobalTransform>(local)
+        .unwrap()
+        .translation();
+    assert!((rendered.x - 1.5).abs() < 1e-3, "{rendered}");
+
+    let position = physics_position(&app, entity);
+    let transform = app.world().get::<Transform>(follower).unwrap();
+    assert!(transform.translation.distance(translation(position)) < 1e-3);
+    let interpolated = position - Vector::X * distance_per_step(&app) * 0.5;
+    app.assert_rendered_translation(entity, interpolated);
+    app.assert_rendered_translation(follower, interpolated);
+}
+
+#[test]
+fn loaded_previous_transform_is_kept() {
+    let mut app = create_app();
+    // Moves bodies like a teleport would, which is interpolated from where the body was before the physics step.
+    app.add_systems(
+        FixedUpdate,
+        |mut q_position: Query<&mut Position, With<RigidBody>>| {
+            for mut position in &mut q_position {
+                position.0 += Vector::X;
+            }
+        },
+    );
+    let previous_position = PreviousPosition(Vector::X * -1.0);
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            Position::default(),
+            Rotation::default(),
+            previous_position,
+            TransformBundle::default(),
+        ))
+        .id();
+    app.world_mut().flush();
+    assert_eq!(
+        app.world().get::<PreviousPosition>(entity),
+        Some(&previous_position)
+    );
+    assert!(app.world().get::<PreviousRotation>(entity).is_some());
+
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(entity, Vector::X * -0.5);
+
+    app.advance_fixed_steps(1);
+    assert_eq!(
+        app.world().get::<PreviousPosition>(entity),
+        Some(&PreviousPosition(Vector::ZERO))
+    );
+    app.assert_rendered_translation(entity, Vector::X * 0.5);
+}
+
+#[test]
+fn previous_transform_is_kept_when_loading_a_scene() {
+    let mut app = create_app();
+    let body = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+    let scene = DynamicSceneBuilder::from_world(app.world())
+        .deny_all()
+        .allow::<RigidBody>()
+        .allow::<Position>()
+        .allow::<Rotation>()
+        .allow::<LinearVelocity>()
+        .allow::<PreviousPosition>()
+        .allow::<PreviousRotation>()
+        .allow::<Transform>()
+        .allow::<GlobalTransform>()
+        .extract_entities([body].into_iter())
+        .build();
+    app.world_mut().despawn(body);
+    let mut entity_map = EntityHashMap::default();
+    scene
+        .write_to_world(app.world_mut(), &mut entity_map)
+        .unwrap();
+
+    let entity = entity_map[&body];
+    let previous_position = app.world().get::<PreviousPosition>(entity).unwrap().0;
+    let position = physics_position(&app, entity);
+    assert_ne!(previous_position, position);
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(entity, previous_position.lerp(position, 0.5));
+
+    app.advance_fixed_steps(1);
+    assert_eq!(
+        app.world().get::<PreviousPosition>(entity),
+        Some(&PreviousPosition(position))
+    );
+    let next_position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position.lerp(next_position, 0.5));
+}
+
+fn snapped_events(app: &App) -> Vec<InterpolationSnapped> {
+    app.world()
+        .resource::<Events<InterpolationSnapped>>()
+        .iter_current_update_events()
+        .copied()
+        .collect()
+}
+
+#[test]
+fn teleported_body_is_snapped() {
+    let mut app = create_app();
+    app.world_mut()
+        .resource_mut::<InterpolationConfig>()
+        .teleport_threshold = Some(10.0);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+
+    let from = app.world().get::<PreviousPosition>(entity).unwrap().0;
+    let to = Vector::X * 100.0;
+    app.world_mut().get_mut::<Position>(entity).unwrap().0 = to;
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(entity, to);
+    assert_eq!(
+        snapped_events(&app),
+        vec![InterpolationSnapped {
+            entity,
+            from,
+            to,
+            reason: SnapReason::Teleported,
+        }]
+    );
+}
+
+#[test]
+fn visual_proxy_of_teleported_body_is_snapped() {
+    let mut app = create_app();
+    app.world_mut()
+        .resource_mut::<InterpolationConfig>()
+        .teleport_threshold = Some(10.0);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    let proxy = app
+        .world_mut()
+        .spawn((TransformBundle::default(), VisualOf::new(entity)))
+        .id();
+    app.advance_fixed_steps(2);
+
+    let to = Vector::X * 100.0;
+    app.world_mut().get_mut::<Position>(entity).unwrap().0 = to;
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(proxy, to);
+}
+
+#[test]
+fn teleported_body_has_no_interpolated_velocity() {
+    let mut app = create_app();
+    app.world_mut()
+        .resource_mut::<InterpolationConfig>()
+        .teleport_threshold = Some(10.0);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(InterpolatedVelocity::default());
+    app.advance_fixed_steps(2);
+
+    app.world_mut().get_mut::<Position>(entity).unwrap().0 = Vector::X * 100.0;
+    app.advance_to_overstep(0.5);
+    let velocity = app.world().get::<InterpolatedVelocity>(entity).unwrap();
+    assert_eq!(velocity.linear, Vector::ZERO);
+}
+
+#[test]
+fn spawned_body_sends_snapped_event() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::X);
+    app.world_mut().flush();
+    assert_eq!(
+        snapped_events(&app),
+        vec![InterpolationSnapped {
+            entity,
+            from: Vector::X,
+            to: Vector::X,
+            reason: SnapReason::Spawned,
+        }]
+    );
+}
+
+#[cfg(feature = "2d")]
+const SPAWN_POSITION: Vector = Vector::new(5.0, 3.0);
+#[cfg(feature = "3d")]
+const SPAWN_POSITION: Vector = Vector::new(5.0, 3.0, 1.0);
+
+/// Spawns a moving body with its `Transform` still at the origin, as a user would when only setting its `Position`.
+fn spawn_body_at_physics_pose(mut commands: Commands) {
+    commands.spawn((
+        RigidBody::Kinematic,
+        Position(SPAWN_POSITION),
+        Rotation::default(),
+        LinearVelocity(Vector::X * SPEED),
+        TransformBundle::default(),
+    ));
+}
+
+fn single_body(app: &mut App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, With<RigidBody>>()
+        .single(app.world())
+}
+
+#[test]
+fn body_spawned_in_startup_renders_at_initial_pose() {
+    let mut app = create_app_with(|app| {
+        app.add_systems(Startup, spawn_body_at_physics_pose);
+    });
+    let entity = single_body(&mut app);
+    app.assert_rendered_translation(entity, SPAWN_POSITION);
+}
+
+#[test]
+fn body_spawned_in_update_renders_at_initial_pose() {
+    let mut app = create_app();
+    app.add_systems(Update, spawn_body_at_physics_pose.run_if(run_once()));
+    app.advance_time(Duration::ZERO);
+    let entity = single_body(&mut app);
+    app.assert_rendered_translation(entity, SPAWN_POSITION);
+
+    app.advance_fixed_steps(1);
+    app.assert_rendered_translation(entity, SPAWN_POSITION);
+}
+
+#[test]
+fn body_spawned_in_fixed_update_renders_at_initial_pose() {
+    let mut app = create_app();
+    app.add_systems(FixedUpdate, spawn_body_at_physics_pose.run_if(run_once()));
+    app.advance_fixed_steps(1);
+    let entity = single_body(&mut app);
+    app.assert_rendered_translation(entity, SPAWN_POSITION);
+
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(
+        entity,
+        SPAWN_POSITION + Vector::X * distance_per_step(&app) * 0.5,
+    );
+}
+
+#[test]
+fn body_spawned_with_only_transform_does_not_interpolate_from_origin() {
+    let mut app = create_app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            LinearVelocity(Vector::X * SPEED),
+            TransformBundle::from_transform(Transform::from_translation(translation(
+                SPAWN_POSITION,
+            ))),
+        ))
+        .id();
+    app.advance_fixed_steps(1);
+    app.assert_rendered_translation(entity, SPAWN_POSITION);
+}
+
+#[test]
+fn body_is_interpolated_when_rigid_body_is_added_last() {
+    let mut app = create_app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            Position(SPAWN_POSITION),
+            Rotation::default(),
+            TransformBundle::default(),
+        ))
+        .id();
+    app.advance_time(Duration::ZERO);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert((RigidBody::Kinematic, LinearVelocity(Vector::X * SPEED)));
+    app.advance_time(Duration::ZERO);
+    assert!(app.world().get::<PreviousPosition>(entity).is_some());
+    app.assert_rendered_translation(entity, SPAWN_POSITION);
+}
+
+#[test]
+fn interpolated_velocity_matches_rendered_motion() {
+    let mut app = create_app();
+    #[cfg(feature = "2d")]
+    let angular_velocity = AngularVelocity(1.0);
+    #[cfg(feature = "3d")]
+    let angular_velocity = AngularVelocity(Vector::Y);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert((angular_velocity, InterpolatedVelocity::default()));
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+
+    let velocity = *app.world().get::<InterpolatedVelocity>(entity).unwrap();
+    assert!((velocity.linear - Vector::X * SPEED).length() < 1e-3);
+    #[cfg(feature = "2d")]
+    assert!((velocity.angular - angular_velocity.0).abs() < 1e-3);
+    #[cfg(feature = "3d")]
+    assert!((velocity.angular - angular_velocity.0).length() < 1e-3);
+}
+
+#[test]
+fn previous_rendered_transform_is_last_frames_global_transform() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.world_mut()
+        .entity_mut(entity)
+        .insert(PreviousRenderedTransform::default());
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.25);
+    let rendered = *app.world().get::<GlobalTransform>(entity).unwrap();
+
+    app.advance_to_overstep(0.5);
+    let previous = app
+        .world()
+        .get::<PreviousRenderedTransform>(entity)
+        .unwrap();
+    assert_eq!(previous.0, rendered);
+    assert_ne!(
+        previous.0,
+        *app.world().get::<GlobalTransform>(entity).unwrap()
+    );
+}
+
+#[test]
+fn follower_uses_interpolated_pose_of_same_frame() {
+    let mut app = create_app();
+    let target = spawn_moving_body(&mut app, Vector::ZERO);
+    #[cfg(feature = "2d")]
+    let offset = Vec2::Y * 2.0;
+    #[cfg(feature = "3d")]
+    let offset = Vec3::Y * 2.0;
+    let follower = app
+        .world_mut()
+        .spawn((
+            InterpolatedFollow::new(target).with_offset(offset),
+            TransformBundle::default(),
+        ))
+        .id();
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+
+    let target_translation = app.world().get::<Transform>(target).unwrap().translation;
+    let follower_translation = app.world().get::<Transform>(follower).unwrap().translation;
+    #[cfg(feature = "2d")]
+    let offset = offset.extend(0.0);
+    assert!(follower_translation.distance(target_translation + offset) < 1e-4);
+}
+
+#[test]
+fn fixed_steps_this_frame_are_counted() {
+    let mut app = create_app();
+    app.advance_fixed_steps(3);
+    assert_eq!(app.world().resource::<FixedStepsThisFrame>().steps, 3);
+
+    app.advance_time(Duration::ZERO);
+    assert_eq!(app.world().resource::<FixedStepsThisFrame>().steps, 0);
+}
+
+#[test]
+fn capped_fixed_steps_snap_bodies() {
+    let mut app = create_app();
+    app.world_mut()
+        .resource_mut::<InterpolationConfig>()
+        .max_fixed_steps_per_frame = NonZeroU32::new(2);
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+    app.advance_to_overstep(0.5);
+
+    app.advance_fixed_steps(5);
+    let fixed_steps = *app.world().resource::<FixedStepsThisFrame>();
+    assert_eq!(fixed_steps.steps, 2);
+    assert!(fixed_steps.capped);
+    let overstep_fraction = app.world().resource::<Time<Fixed>>().overstep_fraction();
+    assert!((overstep_fraction - 0.5).abs() < 1e-3);
+
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position);
+    assert_eq!(
+        snapped_events(&app)
+            .iter()
+            .map(|event| event.reason)
+            .collect::<Vec<_>>(),
+        vec![SnapReason::FixedStepsCapped]
+    );
+}
+
+#[test]
+fn physics_in_fixed_post_update_is_not_reported() {
+    let app = create_app();
+    assert_eq!(validation::physics_schedule_problem(&app), None);
+}
+
+#[test]
+fn physics_outside_fixed_post_update_is_reported() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+        bevy::scene::ScenePlugin,
+        PhysicsPlugins::new(Update),
+        AvianInterpolationPlugin::default(),
+    ))
+    .init_asset::<bevy::render::mesh::Mesh>();
+    assert!(validation::physics_schedule_problem(&app).is_some());
+}
+
+fn drive_transform(mut q_driven: Query<&mut Transform, With<DriveFromTransform>>) {
+    for mut transform in &mut q_driven {
+        transform.translation.x += 1.0;
+    }
+}
+
+#[test]
+fn body_driven_from_transform_is_moved_and_interpolated() {
+    let mut app = create_app();
+    app.add_systems(FixedUpdate, drive_transform);
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            Position::default(),
+            Rotation::default(),
+            DriveFromTransform,
+            TransformBundle::default(),
+        ))
+        .id();
+    app.advance_fixed_steps(2);
+    assert_eq!(physics_position(&app, entity), Vector::X * 2.0);
+
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(entity, Vector::X * 1.5);
+    assert_eq!(physics_position(&app, entity), Vector::X * 2.0);
+}
+
+#[test]
+fn body_not_driven_from_transform_keeps_its_precise_position() {
+    let mut app = create_app();
+    let position = Vector::X / 3.0;
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            Position(position),
+            Rotation::default(),
+            DriveFromTransform,
+            TransformBundle::default(),
+        ))
+        .id();
+    app.advance_fixed_steps(1);
+    app.advance_to_overstep(0.5);
+    app.advance_fixed_steps(3);
+    assert_eq!(physics_position(&app, entity), position);
+}
+
+#[test]
+fn body_driven_from_transform_below_rotated_parent_moves_in_parent_space() {
+    let mut app = create_app();
+    app.add_systems(FixedUpdate, drive_transform);
+    let parent_position = Vector::X * 10.0;
+    let parent = app
+        .world_mut()
+        .spawn(TransformBundle::from_transform(
+            Transform::from_translation(translation(parent_position))
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+        ))
+        .id();
+    // Propagate the parent's `GlobalTransform` before the body is spawned below it.
+    app.advance_time(Duration::ZERO);
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            Position(parent_position),
+            Rotation::default(),
+            DriveFromTransform,
+            TransformBundle::default(),
+        ))
+        .set_parent(parent)
+        .id();
+    app.advance_fixed_steps(2);
+    // The local x axis of the parent points along the global y axis.
+    assert!(
+        physics_position(&app, entity).distance(parent_position + Vector::Y * 2.0) < 1e-3,
+        "the body was not moved along the rotated parent's x axis"
+    );
+
+    app.advance_to_overstep(0.5);
+    app.assert_rendered_translation(entity, parent_position + Vector::Y * 1.5);
+    app.assert_rendered_rotation(entity, Rotation::default());
+}
+
+fn sync_config(app: &App) -> (bool, bool) {
+    let sync_config = app.world().resource::<avian::sync::SyncConfig>();
+    (
+        sync_config.position_to_transform,
+        sync_config.transform_to_position,
+    )
+}
+
+#[test]
+fn disabling_interpolation_restores_sync_config_and_transforms() {
+    let mut app = create_app();
+    assert_eq!(sync_config(&app), (false, false));
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(2);
+    app.advance_to_overstep(0.5);
+
+    app.insert_resource(InterpolationEnabled(false));
+    app.advance_time(Duration::ZERO);
+    let original = avian::sync::SyncConfig::default();
+    assert_eq!(
+        sync_config(&app),
+        (
+            original.position_to_transform,
+            original.transform_to_position
+        )
+    );
+    let position = physics_position(&app, entity);
+    app.assert_rendered_translation(entity, position);
+}
+
+#[test]
+fn disabling_interpolation_resyncs_settled_bodies() {
+    let mut app = create_app();
+    let entity = app
+        .world_mut()
+        .spawn((
+            RigidBody::Kinematic,
+            Position::default(),
+            Rotation::default(),
+            TransformBundle::default(),
+        ))
+        .id();
+    app.advance_fixed_steps(2);
+    assert!(app.world().get::<Settled>(entity).is_some());
+
+    app.world_mut().get_mut::<Position>(entity).unwrap().0 = Vector::X * 5.0;
+    app.insert_resource(InterpolationEnabled(false));
+    app.advance_time(Duration::ZERO);
+    app.assert_rendered_translation(entity, Vector::X * 5.0);
+}
+
+#[test]
+fn reenabling_interpolation_overrides_sync_config_and_resets_previous_transform() {
+    let mut app = create_app();
+    let entity = spawn_moving_body(&mut app, Vector::ZERO);
+    app.advance_fixed_steps(1);
+    app.insert_resource(InterpolationEnabled(false));
+    app.advance_fixed_steps(3);
+
+    app.insert_resource(InterpolationEnabled(true));
+    app.advance_time(Duration::ZERO);
+    assert_eq!(sync_config(&app), (false, false));
+    let position = physics_position(&app, entity);
+    assert_eq!(
+        app.world().get::<PreviousPosition>(entity).unwrap().0,
+        position
+    );
+    app.assert_rendered_translation(entity, position);
+}
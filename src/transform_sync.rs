@@ -1,22 +1,105 @@
-use crate::prelude::*;
+use crate::{
+    interpolate::{sync_transforms_to_physics, wake_all_settled_bodies},
+    prelude::*,
+};
 use avian::sync::SyncConfig;
 use bevy::ecs::system::RunSystemOnce as _;
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<InterpolationEnabled>();
+    app.init_resource::<InterpolationEnabled>();
+    app.init_resource::<OriginalSyncConfig>();
     app.world_mut().run_system_once(disable_transform_sync);
     app.add_systems(
         PreUpdate,
-        disable_transform_sync.run_if(resource_added::<SyncConfig>),
+        (
+            disable_transform_sync.run_if(resource_added::<SyncConfig>),
+            (
+                toggle_transform_sync,
+                // Settled bodies are skipped when writing transforms, but may have been moved since they settled.
+                (wake_all_settled_bodies, sync_transforms_to_physics)
+                    .chain()
+                    .run_if(resource_equals(InterpolationEnabled(false))),
+                (reset_previous_transform, wake_all_settled_bodies)
+                    .run_if(resource_equals(InterpolationEnabled(true))),
+            )
+                .chain()
+                .run_if(resource_changed::<InterpolationEnabled>),
+        )
+            .chain(),
     );
 }
 
-fn disable_transform_sync(sync_config: Option<ResMut<SyncConfig>>) {
+/// Whether [`AvianInterpolationPlugin`] is currently active. This is `true` by default.
+///
+/// Set this to `false` to turn off interpolation entirely at runtime, e.g. for a photo mode or a dedicated server.
+/// While disabled, the [`SyncConfig`] you had before adding [`AvianInterpolationPlugin`] is restored
+/// and the [`Transform`] of all rigid bodies is set to their current physics transform.
+/// Note that if you disabled [`SyncPlugin`], nothing will keep the [`Transform`]s in sync with the physics while
+/// interpolation is disabled.
+///
+/// Setting this back to `true` disables transform syncing again and resumes interpolating from the current physics transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Resource, Reflect, Deref, DerefMut)]
+#[reflect(Resource, Default, PartialEq)]
+pub struct InterpolationEnabled(pub bool);
+
+impl Default for InterpolationEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// The [`SyncConfig`] that was active before we overrode it.
+#[derive(Debug, Default, Resource)]
+struct OriginalSyncConfig(Option<SyncConfig>);
+
+fn disable_transform_sync(
+    interpolation_enabled: Res<InterpolationEnabled>,
+    sync_config: Option<ResMut<SyncConfig>>,
+    mut original_sync_config: ResMut<OriginalSyncConfig>,
+) {
+    if !interpolation_enabled.0 {
+        return;
+    }
     let Some(mut sync_config) = sync_config else {
         // User either disabled `SyncPlugin` or configured `AvianInterpolationPlugin` before `PhysicsPlugins`.
         return;
     };
+    if original_sync_config.0.is_none() {
+        original_sync_config.0 = Some(sync_config.clone());
+    }
     *sync_config = SyncConfig {
         position_to_transform: false,
         transform_to_position: false,
     };
 }
+
+fn toggle_transform_sync(
+    interpolation_enabled: Res<InterpolationEnabled>,
+    sync_config: Option<ResMut<SyncConfig>>,
+    mut original_sync_config: ResMut<OriginalSyncConfig>,
+) {
+    if interpolation_enabled.0 {
+        disable_transform_sync(interpolation_enabled, sync_config, original_sync_config);
+        return;
+    }
+    let (Some(mut sync_config), Some(original)) = (sync_config, original_sync_config.0.take())
+    else {
+        return;
+    };
+    *sync_config = original;
+}
+
+fn reset_previous_transform(
+    mut q_previous: Query<(
+        &Position,
+        &Rotation,
+        &mut PreviousPosition,
+        &mut PreviousRotation,
+    )>,
+) {
+    for (position, rotation, mut previous_position, mut previous_rotation) in &mut q_previous {
+        *previous_position = (*position).into();
+        *previous_rotation = (*rotation).into();
+    }
+}
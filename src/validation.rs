@@ -0,0 +1,214 @@
+use std::fmt::Display;
+
+use avian::sync::SyncConfig;
+use bevy::ecs::schedule::{NodeId, ScheduleGraph};
+#[cfg(debug_assertions)]
+use bevy::{
+    app::RunFixedMainLoop,
+    ecs::{component::Tick, entity::EntityHashSet, system::SystemChangeTick},
+};
+
+use crate::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    if !app.is_plugin_added::<PhysicsSchedulePlugin>() {
+        report(
+            "`AvianInterpolationPlugin` was added before `PhysicsPlugins`. \
+            Please add it after `PhysicsPlugins` so that it can configure the physics plugins correctly.",
+        );
+    }
+    app.add_systems(
+        Update,
+        (
+            validate_sync_config.run_if(
+                resource_exists::<SyncConfig>
+                    .and_then(resource_changed::<SyncConfig>)
+                    .and_then(resource_equals(InterpolationEnabled(true))),
+            ),
+            validate_interpolated_bodies,
+        ),
+    );
+
+    #[cfg(debug_assertions)]
+    {
+        app.init_resource::<LastInterpolationTick>()
+            .init_resource::<WarnedAboutTransformWrites>();
+        app.add_systems(
+            RunFixedMainLoop,
+            (
+                warn_about_transform_writes
+                    .run_if(resource_equals(InterpolationEnabled(true)))
+                    .after(AvianInterpolationVariableSystem::First)
+                    .before(AvianInterpolationVariableSystem::Interpolate),
+                record_interpolation_tick
+                    .after(AvianInterpolationVariableSystem::Interpolate)
+                    .before(AvianInterpolationVariableSystem::Last),
+            ),
+        );
+    }
+}
+
+/// Validates the parts of the app that can only be inspected once all plugins have been built.
+pub(super) fn validate_physics_schedule(app: &App) {
+    if let Some(problem) = physics_schedule_problem(app) {
+        report(problem);
+    }
+}
+
+/// Describes what is wrong with the way the physics are scheduled, if anything.
+pub(crate) fn physics_schedule_problem(app: &App) -> Option<&'static str> {
+    if !app.is_plugin_added::<PhysicsSchedulePlugin>() {
+        return Some(
+            "`AvianInterpolationPlugin` was added without `PhysicsPlugins`. \
+            Nothing will be interpolated.",
+        );
+    }
+    let step_simulation: &dyn SystemSet = &PhysicsSet::StepSimulation;
+    // The set itself also exists when something is merely ordered relative to it, so look for systems in it.
+    let runs_in_fixed_post_update = app
+        .get_schedule(FixedPostUpdate)
+        .map(|schedule| {
+            let graph = schedule.graph();
+            graph
+                .system_sets()
+                .filter(|(_, set, _)| *set == step_simulation)
+                .any(|(id, _, _)| set_contains_systems(graph, id))
+        })
+        .unwrap_or_default();
+    if !runs_in_fixed_post_update {
+        return Some(
+            "The physics simulation is not running in `FixedPostUpdate`. \
+            `AvianInterpolationPlugin` assumes that `Time<Fixed>` drives the physics, \
+            so the interpolated transforms will be wrong.",
+        );
+    }
+    None
+}
+
+fn set_contains_systems(graph: &ScheduleGraph, set: NodeId) -> bool {
+    graph
+        .hierarchy()
+        .graph()
+        .neighbors(set)
+        .any(|child| child.is_system() || set_contains_systems(graph, child))
+}
+
+fn validate_sync_config(sync_config: Res<SyncConfig>) {
+    if sync_config.position_to_transform || sync_config.transform_to_position {
+        report(
+            "`SyncConfig` was changed to enable transform syncing while `AvianInterpolationPlugin` is active. \
+            The transform syncing and the interpolation will fight over the `Transform` of rigid bodies.",
+        );
+    }
+}
+
+fn validate_interpolated_bodies(
+    q_interpolate_transform_fields: Query<
+        (Entity, Option<&Name>, Option<&RigidBody>),
+        (
+            With<InterpolateTransformFields>,
+            Or<(Added<InterpolateTransformFields>, Changed<RigidBody>)>,
+        ),
+    >,
+) {
+    for (entity, maybe_name, maybe_rigid_body) in &q_interpolate_transform_fields {
+        match maybe_rigid_body {
+            Some(rigid_body) if rigid_body.is_static() => report(format!(
+                "{} is a static rigid body with `InterpolateTransformFields`. \
+                Static bodies are not interpolated, so this has no effect.",
+                entity_name(entity, maybe_name)
+            )),
+            None => report(format!(
+                "{} has `InterpolateTransformFields` but is not a rigid body. \
+                Only rigid bodies are interpolated, so this has no effect.",
+                entity_name(entity, maybe_name)
+            )),
+            _ => {}
+        }
+    }
+}
+
+/// The tick right after the last interpolation pass. Any change to a [`Transform`] that is newer than this
+/// was not made by the interpolation.
+#[cfg(debug_assertions)]
+#[derive(Debug, Default, Resource)]
+struct LastInterpolationTick(Option<Tick>);
+
+/// The entities [`warn_about_transform_writes`] already warned about, so that each is only reported once.
+#[cfg(debug_assertions)]
+#[derive(Debug, Default, Resource, Deref)]
+pub(crate) struct WarnedAboutTransformWrites(EntityHashSet);
+
+#[cfg(debug_assertions)]
+fn record_interpolation_tick(
+    ticks: SystemChangeTick,
+    mut last_interpolation_tick: ResMut<LastInterpolationTick>,
+) {
+    last_interpolation_tick.0 = Some(ticks.this_run());
+}
+
+#[cfg(debug_assertions)]
+fn warn_about_transform_writes(
+    ticks: SystemChangeTick,
+    last_interpolation_tick: Res<LastInterpolationTick>,
+    mut warned: ResMut<WarnedAboutTransformWrites>,
+    q_transform: Query<
+        (
+            Entity,
+            Option<&Name>,
+            Ref<Transform>,
+            Ref<PreviousPosition>,
+            Option<&InterpolateTransformFields>,
+        ),
+        (Changed<Transform>, Without<DriveFromTransform>),
+    >,
+) {
+    let Some(last_interpolation_tick) = last_interpolation_tick.0 else {
+        return;
+    };
+    for (entity, maybe_name, transform, previous_position, maybe_interpolate_transform_fields) in
+        &q_transform
+    {
+        // Newly spawned bodies get their `Transform` initialized by Avian, which is fine.
+        if transform.is_added() || previous_position.is_added() {
+            continue;
+        }
+        if maybe_interpolate_transform_fields.is_some_and(|interpolate_transform_fields| {
+            interpolate_transform_fields.translation == InterpolationMode::None
+                || interpolate_transform_fields.rotation == InterpolationMode::None
+        }) {
+            // The user is in charge of at least part of the `Transform`, which we cannot tell apart from the rest.
+            continue;
+        }
+        if !transform
+            .last_changed()
+            .is_newer_than(last_interpolation_tick, ticks.this_run())
+        {
+            continue;
+        }
+        if warned.0.insert(entity) {
+            warn!(
+                "{} had its `Transform` changed by something other than `AvianInterpolationPlugin`. \
+                The change will be overwritten by the interpolation as soon as the body moves. \
+                Change its `Position` and `Rotation` instead, or set the fields of its `InterpolateTransformFields` \
+                that you want to control yourself to `InterpolationMode::None`.",
+                entity_name(entity, maybe_name)
+            );
+        }
+    }
+}
+
+/// Reports a misconfiguration. This is an error in debug builds and a warning in release builds.
+pub(crate) fn report(message: impl Display) {
+    #[cfg(debug_assertions)]
+    error!("{message}");
+    #[cfg(not(debug_assertions))]
+    warn!("{message}");
+}
+
+pub(crate) fn entity_name(entity: Entity, maybe_name: Option<&Name>) -> String {
+    match maybe_name {
+        Some(name) => format!("Entity {entity} (\"{name}\")"),
+        None => format!("Entity {entity}"),
+    }
+}
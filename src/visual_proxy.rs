@@ -0,0 +1,195 @@
+use bevy::{
+    app::RunFixedMainLoop,
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+};
+
+use crate::{
+    interpolate::{
+        interpolated_pose, relative_to_parent_global_transform, rendered_alpha, InterpolateStep,
+        Settled, WriteThresholds,
+    },
+    prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<VisualOf>();
+    app.observe(unregister_visual_proxy);
+    app.add_systems(
+        RunFixedMainLoop,
+        (
+            register_visual_proxies
+                .after(AvianInterpolationVariableSystem::First)
+                .before(InterpolateStep::Snap),
+            // Not part of the interpolation itself, so that proxies keep following their bodies while it is disabled.
+            interpolate_visual_proxies
+                .after(AvianInterpolationVariableSystem::First)
+                .after(InterpolateStep::Snap)
+                .before(AvianInterpolationVariableSystem::Follow),
+        )
+            .chain(),
+    );
+}
+
+/// Turns this entity into a visual proxy of a rigid body.
+///
+/// The proxy gets the interpolated pose of the rigid body, while the [`Transform`] of the rigid body itself
+/// is kept at its current physics transform, as if it used [`InterpolationMode::Last`].
+/// This way, gameplay code can keep reading the exact physics [`Transform`] of the rigid body,
+/// while the proxy holds the meshes, sprites, or anything else that should move smoothly.
+///
+/// The proxy should not be a child of the rigid body, as it would then inherit its physics transform.
+/// Any number of proxies can follow the same rigid body. To make a proxy follow another rigid body,
+/// insert a new [`VisualOf`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct VisualOf(Entity);
+
+impl MapEntities for VisualOf {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+impl VisualOf {
+    /// Turns this entity into a visual proxy of the rigid body `body`.
+    pub fn new(body: Entity) -> Self {
+        Self(body)
+    }
+
+    /// The rigid body this entity is a visual proxy of.
+    pub fn body(&self) -> Entity {
+        self.0
+    }
+}
+
+/// The visual proxies of a rigid body, i.e. the entities with a [`VisualOf`] pointing to it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Component, Deref)]
+pub(crate) struct VisualProxies(Vec<Entity>);
+
+/// The rigid body whose [`VisualProxies`] contain this proxy.
+/// Differs from [`VisualOf`] when the proxy was just pointed at another rigid body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+struct RegisteredBody(Entity);
+
+fn register_visual_proxies(
+    mut commands: Commands,
+    q_changed: Query<(Entity, &VisualOf, Option<&RegisteredBody>), Changed<VisualOf>>,
+) {
+    for (proxy, visual_of, maybe_registered_body) in &q_changed {
+        if let Some(registered_body) = maybe_registered_body {
+            if registered_body.0 == visual_of.0 {
+                continue;
+            }
+            remove_from_visual_proxies(&mut commands, registered_body.0, proxy);
+        }
+        commands.entity(proxy).insert(RegisteredBody(visual_of.0));
+        let Some(mut body) = commands.get_entity(visual_of.0) else {
+            continue;
+        };
+        body.add(
+            move |mut body: EntityWorldMut| match body.get_mut::<VisualProxies>() {
+                Some(mut proxies) => proxies.0.push(proxy),
+                None => {
+                    body.insert(VisualProxies(vec![proxy]));
+                }
+            },
+        );
+    }
+}
+
+fn unregister_visual_proxy(
+    trigger: Trigger<OnRemove, VisualOf>,
+    mut commands: Commands,
+    q_registered_body: Query<&RegisteredBody>,
+) {
+    let proxy = trigger.entity();
+    let Ok(registered_body) = q_registered_body.get(proxy) else {
+        return;
+    };
+    remove_from_visual_proxies(&mut commands, registered_body.0, proxy);
+    commands.entity(proxy).remove::<RegisteredBody>();
+}
+
+fn remove_from_visual_proxies(commands: &mut Commands, body: Entity, proxy: Entity) {
+    let Some(mut body) = commands.get_entity(body) else {
+        return;
+    };
+    body.add(move |mut body: EntityWorldMut| {
+        let Some(mut proxies) = body.get_mut::<VisualProxies>() else {
+            return;
+        };
+        proxies.0.retain(|&entity| entity != proxy);
+        if proxies.0.is_empty() {
+            body.remove::<VisualProxies>();
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn interpolate_visual_proxies(
+    fixed_time: Res<Time<Fixed>>,
+    config: Res<InterpolationConfig>,
+    enabled: Res<InterpolationEnabled>,
+    length_unit: Option<Res<PhysicsLengthUnit>>,
+    render_origin: Option<Res<RenderOrigin>>,
+    mut q_proxy: Query<(Ref<VisualOf>, &mut Transform, Option<&Parent>)>,
+    q_body: Query<(
+        &Position,
+        &Rotation,
+        &PreviousPosition,
+        &PreviousRotation,
+        Option<&InterpolateTransformFields>,
+        Has<Settled>,
+    )>,
+    q_global_transform: Query<&GlobalTransform>,
+) {
+    let alpha = rendered_alpha(&config, &fixed_time, &enabled);
+    let render_origin = RenderOrigin::get(render_origin.as_deref());
+    let thresholds = WriteThresholds::new(&config, length_unit.as_deref());
+    q_proxy
+        .par_iter_mut()
+        .batching_strategy(config.batching_strategy())
+        .for_each(|(visual_of, mut transform, maybe_parent)| {
+            let Ok((
+                position,
+                rotation,
+                previous_position,
+                previous_rotation,
+                maybe_interpolate_transform_fields,
+                is_settled,
+            )) = q_body.get(visual_of.0)
+            else {
+                // The rigid body was despawned or is static.
+                return;
+            };
+            // The proxies of a settled body already have its final pose, unless they were just pointed at it
+            // or their parent may have moved.
+            if is_settled && !visual_of.is_changed() && maybe_parent.is_none() {
+                return;
+            }
+            let (translation, rotation) = interpolated_pose(
+                alpha,
+                render_origin,
+                maybe_interpolate_transform_fields
+                    .copied()
+                    .unwrap_or_default(),
+                (position, rotation),
+                (previous_position, previous_rotation),
+            );
+            let maybe_parent_global_transform =
+                maybe_parent.and_then(|parent| q_global_transform.get(parent.get()).ok());
+            let (translation, rotation) = relative_to_parent_global_transform(
+                translation,
+                rotation,
+                maybe_parent_global_transform,
+            );
+            // In 2D, the proxy keeps its own z so that it can be layered independently of the rigid body.
+            #[cfg(feature = "2d")]
+            let translation = translation
+                .map(|translation| translation.truncate().extend(transform.translation.z));
+            thresholds.write(&mut transform, translation, rotation);
+        });
+}